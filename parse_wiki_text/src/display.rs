@@ -18,7 +18,7 @@ impl std::fmt::Display for Node<'_> {
 
             Node::Heading { nodes, level, .. } => {
                 match nodes.get(0) {
-                    Some(node) => write!(f, "\x1b[1m{}\x1b[0m", node),
+                    Some(node) => write!(f, "{}", node),
                     None => Err(Error),
                 }
             },
@@ -165,12 +165,12 @@ fn expand_template(name: &str, parameters: &Vec<Parameter>) -> Result<String, Er
                 buffer.push_str(&format!("{part}"));
             }
         },
-        x if x.contains("-IPA") => { // todo
-            let ipa = get(parameters, 0)?;
-            buffer.push_str(&format!("IPA: {ipa}"));
+        x if x.contains("-IPA") => { // todo: it's a respelling, the IPA gets worked out from it
+            let respelling = get(parameters, 0)?;
+            buffer.push_str(&format!("Respelling: {respelling}"));
         },
         "top4" | "bottom" | "head" | "head-lite" | "was wotd" | "wikipedia" => (),
-        _ => buffer.push_str(&format!("\x1b[1m--{name}--\x1b[0m")),
+        _ => buffer.push_str(&format!("--{name}--")),
     };
     return Ok(buffer);
 }
@@ -0,0 +1,75 @@
+use std::{fs, path::PathBuf};
+
+use crate::state::State;
+
+// the config file is plain `key = value` lines, with `#` for comments and commas between list items:
+//   lang = Latin
+//   exclude = Anagrams, References
+//   include = Synonyms
+//   color = auto
+//   theme.headword = bold yellow
+//   format = markdown
+//   link = https://en.wiktionary.org/wiki/{}
+
+// i really miss static blocks
+/// The sections hidden unless asked for, if the config file doesn't say otherwise
+pub const default_excluded_sections: &[&str; 14] =
+    &["Synonyms", "Antonyms", "Hyponyms", "Anagrams", "Translations",
+    "Pronunciation", "Declension", "Inflection",
+    "Derived terms", "Related terms", "See also", "Further reading",
+    "References", "Alternative forms"];
+
+/// `$XDG_CONFIG_HOME/dictionarium/config`, or `~/.config/dictionarium/config`
+pub fn path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME").map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    return Some(base.join("dictionarium").join("config"));
+}
+
+/// Applies the config file on top of `state`, if there is one. Flags get applied after this, so they win.
+pub fn load(state: &mut State) {
+    let Some(path) = path() else {
+        return;
+    };
+    let Ok(contents) = fs::read_to_string(&path) else {
+        return;
+    };
+    for (number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            println!("Ignoring line {} of {}: expected `key = value`.", number + 1, path.display());
+            continue;
+        };
+        apply(state, key.trim(), value.trim());
+    }
+}
+
+fn list(value: &str) -> Vec<String> {
+    return value.split(',').map(|item| item.trim().to_string()).filter(|item| !item.is_empty()).collect();
+}
+
+fn apply(state: &mut State, key: &str, value: &str) {
+    match key {
+        "lang" => state.lang = value.to_string(),
+        "exclude" => state.exclude = list(value),
+        "include" => state.include = list(value),
+        "color" => match crate::theme::Color::parse(value) {
+            Some(color) => state.color = color,
+            None => println!("Expected always, never or auto for \"color\", not \"{}\".", value),
+        },
+        "format" => match crate::markup::Format::parse(value) {
+            Some(format) => state.format = format,
+            None => println!("Expected terminal, markdown or html for \"format\", not \"{}\".", value),
+        },
+        "link" => state.link = value.to_string(),
+        key if key.starts_with("theme.") => {
+            if let Err(error) = state.theme.set(&key["theme.".len()..], value) {
+                println!("Ignoring {}: {}.", key, error);
+            }
+        },
+        _ => println!("Unknown config option \"{}\".", key),
+    }
+}
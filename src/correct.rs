@@ -1,4 +1,5 @@
 // http://norvig.com/spell-correct.html
-pub fn correct(word: &str) -> Option<&str> {
-    todo!();
+// todo: the actual correcting. until then there's never anything to suggest
+pub fn correct(_word: &str) -> Option<&str> {
+    return None;
 }
@@ -0,0 +1,122 @@
+use parse_wiki_text::*;
+
+use crate::{entry, etymology, languages};
+
+// https://en.wiktionary.org/wiki/Template:desc
+// a descendants section is a nested bulleted list, one language per line:
+//   * {{desc|fr|banque|bor=1}}
+//   * Old French: {{l|fro|banc}}
+//   ** {{desc|fr|banc}}
+//   * {{desctree|es|banco}}
+// where {{desctree}} means "and see that page for its own descendants"
+
+/// A line of a descendants tree, and the lines nested under it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Descendant {
+    pub lang: String,
+    pub terms: Vec<String>,
+    /// How the term came to be: "borrowed", "calque", "learned" and so on. Empty when inherited.
+    pub markers: Vec<&'static str>,
+    pub transliteration: Option<String>,
+    /// From {{desctree}}: the term's own page has more descendants
+    pub tree: bool,
+    pub children: Vec<Descendant>,
+}
+
+const markers: &[(&str, &str)] = &[
+    ("bor", "borrowed"), ("lbor", "learned"), ("slb", "semi-learned"), ("clq", "calque"),
+    ("cal", "calque"), ("pclq", "partial calque"), ("sml", "semantic loan"), ("sl", "semantic loan"),
+    ("der", "derived"), ("translit", "transliteration"), ("unc", "uncertain"),
+];
+
+/// Parses a descendants list into one tree per top-level line
+pub fn parse(node: &Node) -> Vec<Descendant> {
+    let mut descendants = Vec::new();
+    if let Node::UnorderedList { items, .. } = node {
+        for item in items {
+            descendants.push(parse_line(&item.nodes));
+        }
+    }
+    return descendants;
+}
+
+fn parse_line(nodes: &[Node]) -> Descendant {
+    let mut descendant = Descendant::default();
+    for node in nodes {
+        match node {
+            // `* Old French: ...`
+            Node::Text { value, .. } if descendant.lang.is_empty() && value.contains(':') => {
+                descendant.lang = value.split(':').next().unwrap_or_default().trim().to_string();
+            },
+            Node::Template { name, parameters, .. } => {
+                let positional = entry::positional(parameters);
+                match entry::first_text(name).unwrap_or_default() {
+                    name @ ("desc" | "desctree" | "descendant" | "descendants tree") => {
+                        if let Some(code) = positional.first() {
+                            descendant.lang = languages::name_or_code(code).to_string();
+                        }
+                        descendant.terms.extend(positional.into_iter().skip(1).filter(|term| !term.is_empty() && term != "-"));
+                        descendant.tree = name == "desctree" || name == "descendants tree";
+                        descendant.transliteration = entry::named(parameters, "tr");
+                        for (key, marker) in markers {
+                            if entry::named(parameters, key).is_some() && !descendant.markers.contains(marker) {
+                                descendant.markers.push(marker);
+                            }
+                        }
+                    },
+                    "l" | "l-lite" | "ll" => descendant.terms.extend(positional.into_iter().nth(1)),
+                    _ => (),
+                }
+            },
+            Node::UnorderedList { .. } => descendant.children.extend(parse(node)),
+            _ => (),
+        }
+    }
+    return descendant;
+}
+
+impl Descendant {
+    /// Fills in the children of {{desctree}} lines from the descendants sections of their own pages,
+    /// `depth` levels down. `lookup` fetches the text of a page by title.
+    pub fn expand(&mut self, depth: usize, lookup: &mut impl FnMut(&str) -> Option<String>) {
+        if depth == 0 {
+            return;
+        }
+        if self.tree && self.children.is_empty() && let Some(term) = self.terms.first()
+        && let Some(text) = lookup(&etymology::page(term, &self.lang)) {
+            let definition = Configuration::default().parse(&text);
+            for section in entry::sections(&definition, &self.lang, "Descendants") {
+                for node in section {
+                    self.children.extend(parse(node));
+                }
+            }
+        }
+        for child in self.children.iter_mut() {
+            child.expand(depth - 1, lookup);
+        }
+    }
+
+    fn write(&self, f: &mut std::fmt::Formatter<'_>, indent: usize) -> std::fmt::Result {
+        write!(f, "{}{}", "  ".repeat(indent), self.lang)?;
+        if !self.terms.is_empty() {
+            write!(f, ": {}", self.terms.join(", "))?;
+        }
+        if let Some(transliteration) = &self.transliteration {
+            write!(f, " ({})", transliteration)?;
+        }
+        if !self.markers.is_empty() {
+            write!(f, " [{}]", self.markers.join(", "))?;
+        }
+        writeln!(f)?;
+        for child in self.children.iter() {
+            child.write(f, indent + 1)?;
+        }
+        return Ok(());
+    }
+}
+
+impl std::fmt::Display for Descendant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return self.write(f, 0);
+    }
+}
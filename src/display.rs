@@ -1,38 +1,87 @@
+use crate::descendants;
+use crate::entry;
+use crate::etymology::Ancestor;
+use crate::forms::FormOf;
+use crate::markup::{self, Format};
+use crate::pronunciation::Pronunciation;
+use crate::sections;
 use crate::state::*;
+use crate::theme::{self, Style, Theme};
 use parse_wiki_text::*;
-
-// i really miss static blocks
-const skippable_headers: &[&str; 15] =
-    &["Synonyms", "Antonyms", "Hyponyms", "Anagrams", "Translations",
-    "Pronunciation", "Declension", "Inflection", "Descendants",
-    "Derived terms", "Related terms", "See also", "Further reading",
-    "References", "Alternative forms"];
+use std::cell::RefCell;
+use std::io::{self, Write};
 
 // now we do somewhat inefficient string manipulation
 // but it's fine because we're working with MUCH smaller strings lol
-pub fn display(definition: String, state: &State) {
-    let definition = Configuration::default().parse(&definition);
-    display_language(&definition, &state.lang);
+pub fn display(title: &str, definition: &str, state: &State, out: &mut impl Write) -> io::Result<()> {
+    return out.write_all(render(title, definition, state).text.as_bytes());
 }
 
-/// Prints only the provided language if present, otherwise prints the first language
-fn display_language(definition: &Output, lang: &str) {
-    let mut has_lang = false;
+/// `display`, but into a string rather than out somewhere
+pub fn display_to_string(title: &str, definition: &str, state: &State) -> String {
+    return render(title, definition, state).text;
+}
+
+/// An entry, laid out
+pub struct Rendering {
+    pub text: String,
+    /// Where its links go, in the order they come up: titles, with any `#Section` on the end
+    pub links: Vec<String>,
+}
+
+/// Lays out an entry as `display` would print it
+pub fn render(title: &str, definition: &str, state: &State) -> Rendering {
+    let definition = Configuration::default().parse(definition);
+    let layout = Layout::new(title, state);
+    // just the article: a page can hold several entries, so `markup::document` goes around them all
+    if layout.format == Format::Html {
+        layout.print(&format!("<article id=\"{}\">", markup::escape(Format::Html, &markup::anchor(title))));
+    }
+    // a redirect is the whole of its page, so all there is to show is where it goes
+    if let Some(Node::Redirect { target, start, end }) = definition.nodes.first() {
+        let mut text = Inline::new(&layout, Style::default());
+        text.push_text("→ ");
+        text.push(&Node::Link { start: *start, end: *end, target, text: Vec::new() });
+        layout.print(&layout.paragraph(&text.finish()));
+    }
+    display_language(title, &definition, &layout, state);
+    if state.links {
+        layout.print(&layout.references());
+    }
+    if layout.format == Format::Html {
+        layout.print("</article>");
+    }
+    return Rendering { text: layout.out.take(), links: layout.links.take() };
+}
+
+/// The language `display_language` will show: the provided one if present, otherwise the first
+pub fn displayed_language<'a>(definition: &'a Output<'a>, lang: &str) -> Option<&'a str> {
+    let mut first = None;
     for node in definition.nodes.iter() {
-        if let Node::Heading { nodes, level, .. } = node
-        && let Some(Node::Text { value, .. }) = nodes.get(0) {
-            if *level == 2 && *value == lang {
-                has_lang = true;
-                break;
+        if let Node::Heading { nodes, level, .. } = node && *level == 2
+        && let Some(value) = entry::first_text(nodes) {
+            if value == lang {
+                return Some(value);
             }
+            first = first.or(Some(value));
         }
     }
+    return first;
+}
+
+/// Prints only the provided language if present, otherwise prints the first language
+fn display_language(title: &str, definition: &Output, layout: &Layout, state: &State) {
+    let lang = state.lang.as_str();
+    let has_lang = displayed_language(definition, lang) == Some(lang);
 
     let mut skipping_heading = false;
+    // a part of speech filtered out by --pos (or an etymology by --etym) takes its subsections with it
+    let mut hidden_below: Option<u8> = None;
     let mut inside_main_heading = false;
-    let mut nodes = definition.nodes.iter().peekable();
-    while let Some(node) = nodes.next() {
-        if let Node::Heading { nodes, level, .. } = node && let Some(Node::Text { value, .. }) = nodes.get(0) {
+    let mut inside_descendants = false;
+    let mut paragraph = Paragraph::new(layout);
+    for node in definition.nodes.iter() {
+        if let Node::Heading { nodes, level, .. } = node && let Some(Node::Text { value, .. }) = nodes.first() {
             // if at a language header
             if *level == 2 {
                 // if we're done with the main heading, break
@@ -44,24 +93,626 @@ fn display_language(definition: &Output, lang: &str) {
                     inside_main_heading = true;
                 }
             }
-            // if the header is in our skippable headers list: skip until the next header
-            if inside_main_heading {
-                skipping_heading = skippable_headers.contains(value);
+            // if the header isn't one we're showing: skip until the next header
+            if inside_main_heading && *level > 2 {
+                if hidden_below.is_some_and(|hidden| *level <= hidden) {
+                    hidden_below = None;
+                }
+                if hidden_below.is_none() && (sections::is_other_part_of_speech(value.trim(), state)
+                || sections::is_other_etymology(value.trim(), state)) {
+                    hidden_below = Some(*level);
+                }
+                skipping_heading = hidden_below.is_some() || !sections::is_visible(value.trim(), state);
+                inside_descendants = value.starts_with("Descendants");
             }
         }
         if inside_main_heading && !skipping_heading {
-            print!("{}", node);
             match node {
-                Node::Heading { .. } => println!(),
-                _ => match nodes.peek() {
-                    Some(Node::Heading { .. }) |
-                    Some(Node::OrderedList { .. }) |
-                    Some(Node::UnorderedList { .. }) |
-                    Some(Node::DefinitionList { .. }) |
-                    None => println!(),
-                    _ => (),
+                Node::Heading { level, .. } => {
+                    paragraph.flush();
+                    let heading = value_of(node);
+                    // homographs get labelled by number, with their etymology alongside: "bank¹ — From..."
+                    if let Some(number) = entry::etymology_number(heading) {
+                        paragraph.label = Some(layout.strong(&format!("{}{}", title, superscript(number))));
+                        continue;
+                    }
+                    if sections::is_part_of_speech(heading.trim()) {
+                        layout.print(&layout.heading(node, *level, &layout.theme.part_of_speech));
+                        // the headword line comes right after
+                        paragraph.inline.base = layout.theme.headword.clone();
+                    } else {
+                        layout.print(&layout.heading(node, *level, &layout.theme.heading));
+                    }
+                    if *level == 2 && state.pronunciation
+                    && let Some(pronunciation) = Pronunciation::parse(definition, heading) {
+                        layout.print(&layout.paragraph(&markup::escape(layout.format, &pronunciation.to_string())));
+                    }
+                },
+                // descendants get drawn as a tree, rather than as a list
+                Node::UnorderedList { .. } if inside_descendants => {
+                    paragraph.flush();
+                    layout.print(&layout.preformatted(&descendants_of(node, state)));
+                },
+                Node::OrderedList { .. } | Node::UnorderedList { .. } | Node::DefinitionList { .. } => {
+                    paragraph.flush();
+                    let mut lines = String::new();
+                    layout.list(node, &[], 0, &mut lines);
+                    layout.print(lines.trim_end_matches('\n'));
+                },
+                Node::Table { captions, rows, .. } => {
+                    paragraph.flush();
+                    layout.print(&layout.table(captions, rows));
+                },
+                Node::ParagraphBreak { .. } => paragraph.flush(),
+                _ => paragraph.inline.push(node),
+            }
+        }
+    }
+    paragraph.flush();
+}
+
+/// How to lay an entry out: in what format, how wide, and in what style
+struct Layout {
+    /// The entry's own title, for links to elsewhere in it
+    title: String,
+    format: Format,
+    width: Option<usize>,
+    theme: Theme,
+    /// Where links to other entries go, with `{}` for the title
+    link: String,
+    /// Whether links can be clickable, in the terminal
+    hyperlinks: bool,
+    /// Where links have gone so far, for numbering them
+    links: RefCell<Vec<String>>,
+    numbered: bool,
+    /// What's been laid out so far
+    out: RefCell<String>,
+}
+
+// the kinds of list item, which each format marks differently
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Item {
+    Sense,
+    Bullet,
+    Details,
+    Term,
+}
+
+impl Layout {
+    fn new(title: &str, state: &State) -> Layout {
+        // markup does its own styling and line breaking
+        let layout = Layout {
+            title: title.to_string(), format: state.format, width: state.width, theme: theme::active(state),
+            link: state.link.clone(), hyperlinks: state.hyperlinks,
+            links: RefCell::new(Vec::new()), numbered: state.links, out: RefCell::new(String::new()),
+        };
+        return match state.format {
+            Format::Terminal => layout,
+            _ => Layout { width: None, theme: Theme::plain(), hyperlinks: false, ..layout },
+        };
+    }
+
+    /// Writes out a block, with the blank line after it that markdown needs to keep blocks apart
+    fn print(&self, block: &str) {
+        if block.is_empty() {
+            return;
+        }
+        let mut out = self.out.borrow_mut();
+        out.push_str(block);
+        out.push('\n');
+        if self.format == Format::Markdown {
+            out.push('\n');
+        }
+    }
+
+    fn heading(&self, node: &Node, level: u8, style: &Style) -> String {
+        let text = value_of(node).trim();
+        // the language is the top of an entry, at level 2
+        let level = level.saturating_sub(1).max(1);
+        return match self.format {
+            Format::Terminal => style.paint(&Rendered(node, &self.theme).to_string()),
+            Format::Markdown => format!("{} {}", "#".repeat(level.into()), markup::escape(self.format, text)),
+            Format::Html => format!("<h{level} id=\"{}\">{}</h{level}>",
+                markup::escape(self.format, &markup::anchor(text)), markup::escape(self.format, text)),
+        };
+    }
+
+    /// Text that stands out, like the word itself
+    fn strong(&self, text: &str) -> String {
+        return match self.format {
+            Format::Terminal => self.theme.headword.paint(text),
+            Format::Markdown => format!("**{}**", markup::escape(self.format, text)),
+            Format::Html => format!("<strong>{}</strong>", markup::escape(self.format, text)),
+        };
+    }
+
+    /// Already escaped inline text, as a paragraph of its own
+    fn paragraph(&self, text: &str) -> String {
+        if text.trim().is_empty() {
+            return String::new();
+        }
+        return match self.format {
+            Format::Terminal => self.fill(text, "", ""),
+            Format::Markdown => text.split_whitespace().collect::<Vec<&str>>().join(" "),
+            Format::Html => format!("<p>{}</p>", text.split_whitespace().collect::<Vec<&str>>().join(" ")),
+        };
+    }
+
+    /// Text laid out already, to be kept just as it is
+    fn preformatted(&self, text: &str) -> String {
+        let text = text.trim_end_matches('\n');
+        return match self.format {
+            Format::Terminal => text.to_string(),
+            Format::Markdown => format!("```\n{}\n```", text),
+            Format::Html => format!("<pre>{}</pre>", markup::escape(self.format, text)),
+        };
+    }
+
+    /// Wraps text to the width, starting the first line with `first` and the rest with `rest`
+    fn fill(&self, text: &str, first: &str, rest: &str) -> String {
+        // single newlines in wikitext are just spaces
+        let text = text.split_whitespace().collect::<Vec<&str>>().join(" ");
+        return match self.width {
+            Some(width) => textwrap::fill(&text, textwrap::Options::new(width).initial_indent(first).subsequent_indent(rest)),
+            None => format!("{}{}", first, text),
+        };
+    }
+
+    /// Lays out a list one item per line, the text of each hanging under its marker,
+    /// and whatever's nested in it (sub-senses, examples, quotations) indented under that.
+    /// `path` is the numbers of the senses it's nested in, for numbering sub-senses "1.1", "1.1.a" and so on.
+    fn list(&self, node: &Node, path: &[usize], indent: usize, lines: &mut String) {
+        if self.format == Format::Html {
+            return self.html_list(node, path, lines);
+        }
+        let items = items(node, path);
+        let markers = items.iter().map(|(item, _, path)| match (self.format, item) {
+            (Format::Markdown, Item::Sense) => format!("{}.", path.last().unwrap_or(&1)),
+            (_, Item::Sense) => sense_number(path),
+            (Format::Markdown, Item::Bullet) => String::from("-"),
+            (_, Item::Bullet) => String::from("•"),
+            // a line indented on its own is a quote, as far as markdown goes
+            (Format::Markdown, Item::Details) if indent == 0 => String::from(">"),
+            (Format::Markdown, Item::Details) => String::from("-"),
+            (_, Item::Details) => String::from(" "),
+            (_, Item::Term) => String::new(),
+        }).collect::<Vec<String>>();
+        // right-aligned, so that 9. and 10. line up (markdown has its own ideas)
+        let digits = match self.format {
+            Format::Terminal => markers.iter().map(|marker| textwrap::core::display_width(marker)).max().unwrap_or_default(),
+            _ => 0,
+        };
+        for ((item, nodes, path), marker) in items.into_iter().zip(markers) {
+            let marker = if marker.is_empty() { marker } else { format!("{:>digits$}", marker) };
+            let hanging = indent + textwrap::core::display_width(&marker) + usize::from(!marker.is_empty());
+            let (text, nested) = self.item(item, nodes, &path);
+            if let Some(text) = text {
+                let marker = match item {
+                    Item::Sense => format!("{} ", self.theme.sense_number.paint(&marker)),
+                    _ if marker.is_empty() => marker,
+                    _ => format!("{} ", marker),
+                };
+                lines.push_str(&self.fill(&text, &format!("{}{}", " ".repeat(indent), marker), &" ".repeat(hanging)));
+                lines.push('\n');
+            }
+            for node in nested {
+                self.list(node, &path, hanging, lines);
+            }
+        }
+    }
+
+    fn html_list(&self, node: &Node, path: &[usize], lines: &mut String) {
+        let tag = match node {
+            Node::OrderedList { .. } => "ol",
+            Node::UnorderedList { .. } => "ul",
+            Node::DefinitionList { .. } => "dl",
+            _ => return,
+        };
+        lines.push_str(&format!("<{}>\n", tag));
+        for (item, nodes, path) in items(node, path) {
+            let (element, attributes) = match item {
+                Item::Sense | Item::Bullet => ("li", ""),
+                Item::Details if path.is_empty() => ("dd", ""),
+                Item::Details => ("dd", " class=\"example\""),
+                Item::Term => ("dt", ""),
+            };
+            let (text, nested) = self.item(item, nodes, &path);
+            lines.push_str(&format!("<{}{}>", element, attributes));
+            lines.push_str(&text.unwrap_or_default());
+            if !nested.is_empty() {
+                lines.push('\n');
+            }
+            for node in nested {
+                self.html_list(node, &path, lines);
+            }
+            lines.push_str(&format!("</{}>\n", element));
+        }
+        lines.push_str(&format!("</{}>\n", tag));
+    }
+
+    // the text of a list item, if it has any, and the lists nested in it
+    fn item<'n>(&self, item: Item, nodes: &'n [Node<'n>], path: &[usize]) -> (Option<String>, Vec<&'n Node<'n>>) {
+        // examples and quotations are the unnumbered lines under a sense
+        let style = match item {
+            Item::Details if !path.is_empty() => self.theme.example.clone(),
+            _ => Style::default(),
+        };
+        let mut text = Inline::new(self, style);
+        let mut nested = Vec::new();
+        for node in nodes {
+            match node {
+                Node::OrderedList { .. } | Node::UnorderedList { .. } | Node::DefinitionList { .. } => nested.push(node),
+                node => text.push(node),
+            }
+        }
+        return ((!text.is_empty()).then(|| text.finish()), nested);
+    }
+
+    fn table(&self, captions: &[TableCaption], rows: &[TableRow]) -> String {
+        let caption = captions.iter().map(|caption| self.inline(&caption.content)).collect::<Vec<String>>().join(" ");
+        let rows = rows.iter().map(|row| row.cells.iter().map(|cell| {
+            (cell.type_ == TableCellType::Heading, self.inline(&cell.content))
+        }).collect::<Vec<(bool, String)>>()).filter(|row| !row.is_empty()).collect::<Vec<Vec<(bool, String)>>>();
+        let columns = rows.iter().map(|row| row.len()).max().unwrap_or_default();
+        let mut lines = Vec::new();
+        match self.format {
+            Format::Terminal => {
+                let widths = (0..columns).map(|column| rows.iter()
+                    .filter_map(|row| row.get(column)).map(|(_, text)| textwrap::core::display_width(text)).max().unwrap_or_default()
+                ).collect::<Vec<usize>>();
+                if !caption.is_empty() {
+                    lines.push(caption);
+                }
+                for row in rows.iter() {
+                    let cells = row.iter().zip(widths.iter()).map(|((heading, text), width)| {
+                        let padded = format!("{}{}", text, " ".repeat(width - textwrap::core::display_width(text)));
+                        if *heading { self.theme.heading.paint(&padded) } else { padded }
+                    }).collect::<Vec<String>>();
+                    lines.push(cells.join(" │ ").trim_end().to_string());
+                }
+            },
+            Format::Markdown => {
+                if !caption.is_empty() {
+                    lines.push(format!("{}\n", caption));
                 }
+                // the first row is the header, whatever it says
+                for (i, row) in rows.iter().enumerate() {
+                    let mut cells = row.iter().map(|(_, text)| text.clone()).collect::<Vec<String>>();
+                    cells.resize(columns, String::new());
+                    lines.push(format!("| {} |", cells.join(" | ")));
+                    if i == 0 {
+                        lines.push(format!("|{}", " --- |".repeat(columns)));
+                    }
+                }
+            },
+            Format::Html => {
+                lines.push(String::from("<table>"));
+                if !caption.is_empty() {
+                    lines.push(format!("<caption>{}</caption>", caption));
+                }
+                for row in rows.iter() {
+                    let cells = row.iter().map(|(heading, text)| {
+                        if *heading { format!("<th>{}</th>", text) } else { format!("<td>{}</td>", text) }
+                    }).collect::<String>();
+                    lines.push(format!("<tr>{}</tr>", cells));
+                }
+                lines.push(String::from("</table>"));
+            },
+        }
+        return lines.join("\n");
+    }
+
+    /// Numbers a link, the same number each time it comes up
+    fn reference(&self, target: &str) -> usize {
+        let mut links = self.links.borrow_mut();
+        if let Some(index) = links.iter().position(|link| link == target) {
+            return index + 1;
+        }
+        links.push(target.to_string());
+        return links.len();
+    }
+
+    /// The numbered list of everywhere the entry's links go, for `--links`
+    fn references(&self) -> String {
+        let links = self.links.borrow().iter().map(|target| markup::url(&self.link, target)).collect::<Vec<String>>();
+        if links.is_empty() {
+            return String::new();
+        }
+        let digits = links.len().to_string().len();
+        return match self.format {
+            Format::Terminal => links.iter().enumerate()
+                .map(|(i, url)| format!("[{:>digits$}] {}", i + 1, url)).collect::<Vec<String>>().join("\n"),
+            Format::Markdown => links.iter().enumerate()
+                .map(|(i, url)| format!("{}. <{}>", i + 1, url)).collect::<Vec<String>>().join("\n"),
+            Format::Html => {
+                let items = links.iter().map(|url| {
+                    let url = markup::escape(self.format, url);
+                    format!("<li><a href=\"{}\">{}</a></li>", url, url)
+                }).collect::<Vec<String>>().join("\n");
+                format!("<ol class=\"links\">\n{}\n</ol>", items)
+            },
+        };
+    }
+
+    // a run of nodes on their own, like a table cell
+    fn inline(&self, nodes: &[Node]) -> String {
+        let mut text = Inline::new(self, Style::default());
+        for node in nodes {
+            text.push(node);
+        }
+        return text.finish().split_whitespace().collect::<Vec<&str>>().join(" ");
+    }
+}
+
+// the items of a list, with the path of sense numbers to each
+fn items<'n>(node: &'n Node<'n>, path: &[usize]) -> Vec<(Item, &'n [Node<'n>], Vec<usize>)> {
+    return match node {
+        Node::OrderedList { items, .. } => items.iter().enumerate()
+            .map(|(i, item)| (Item::Sense, item.nodes.as_slice(), [path, &[i + 1]].concat())).collect(),
+        Node::UnorderedList { items, .. } => items.iter()
+            .map(|item| (Item::Bullet, item.nodes.as_slice(), path.to_vec())).collect(),
+        Node::DefinitionList { items, .. } => items.iter().map(|item| match item.type_ {
+            DefinitionListItemType::Details => (Item::Details, item.nodes.as_slice(), path.to_vec()),
+            DefinitionListItemType::Term => (Item::Term, item.nodes.as_slice(), path.to_vec()),
+        }).collect(),
+        _ => Vec::new(),
+    };
+}
+
+/// Inline text, put together a node at a time. The parser only tells us where bold and italic
+/// switch on and off, so we keep track of which are on, and close them at the end.
+struct Inline<'a> {
+    layout: &'a Layout,
+    /// The style of the whole run, like the headword line's or an example's
+    base: Style,
+    text: String,
+    bold: bool,
+    italic: bool,
+    // whether there's any text yet, and which styles we've actually written out
+    started: bool,
+    open: bool,
+    shown: (bool, bool),
+}
+
+impl<'a> Inline<'a> {
+    fn new(layout: &'a Layout, base: Style) -> Inline<'a> {
+        return Inline { layout, base, text: String::new(), bold: false, italic: false, started: false, open: false, shown: (false, false) };
+    }
+
+    fn push(&mut self, node: &Node) {
+        match node {
+            Node::Bold { .. } => {
+                self.bold = !self.bold;
+                self.restyle();
+            },
+            Node::Italic { .. } => {
+                self.italic = !self.italic;
+                self.restyle();
+            },
+            Node::BoldItalic { .. } => {
+                self.bold = !self.bold;
+                self.italic = !self.italic;
+                self.restyle();
+            },
+            // {{ux|en|example|translation}}: the example goes through us, to keep its bolded headword
+            Node::Template { name, parameters, .. } if matches!(entry::first_text(name), Some("ux" | "uxi" | "usex" | "ux-lite")) => {
+                let mut positional = parameters.iter().filter(|parameter| parameter.name.is_none()).skip(1);
+                for node in positional.next().map_or(&[][..], |example| example.value.as_slice()) {
+                    self.push(node);
+                }
+                let translation = entry::named(parameters, "t").or_else(|| entry::named(parameters, "translation"))
+                    .or_else(|| positional.next().map(|translation| entry::plain_text(translation.value.iter())));
+                if let Some(translation) = translation.filter(|translation| !translation.is_empty()) {
+                    self.push_text(&format!(" ― {}", translation));
+                }
+            },
+            Node::Link { target, text, .. } => {
+                let text = entry::plain_text(text.iter());
+                let text = if text.is_empty() { target.to_string() } else { text };
+                let format = self.layout.format;
+                // outside of an html page of our own, `[[#Verb]]` has to say which page it's on
+                let target = match target.strip_prefix('#') {
+                    Some(section) if format != Format::Html => format!("{}#{}", self.layout.title, section),
+                    _ => target.to_string(),
+                };
+                let url = markup::url(&self.layout.link, &target);
+                // numbered to go with the list at the end, for --links
+                let number = self.layout.reference(&target);
+                let reference = if self.layout.numbered { format!("[{}]", number) } else { String::new() };
+                match format {
+                    // OSC 8, where the terminal can: https://gist.github.com/egmontkob/eb114294efbcd5adb1944c9f3cb5feda
+                    Format::Terminal if self.layout.hyperlinks => {
+                        let text = self.layout.theme.link.paint(&text);
+                        self.push_markup(&format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\{}", url, text, reference));
+                    },
+                    Format::Terminal => self.push_markup(&format!("{}{}", self.layout.theme.link.paint(&text), reference)),
+                    Format::Markdown => self.push_markup(&format!("[{}]({})", markup::escape(format, &text), url)),
+                    Format::Html => self.push_markup(&format!("<a href=\"{}\">{}</a>", markup::escape(format, &url), markup::escape(format, &text))),
+                }
+            },
+            // tables have no business being inline, and the parser can't display them anyway
+            Node::Table { .. } => (),
+            node => self.push_text(&Rendered(node, &self.layout.theme).to_string()),
+        }
+    }
+
+    fn push_text(&mut self, text: &str) {
+        self.push_markup(&markup::escape(self.layout.format, text));
+    }
+
+    fn push_markup(&mut self, text: &str) {
+        let text = if self.started { text } else { text.trim_start() };
+        if text.is_empty() {
+            return;
+        }
+        if !self.started {
+            self.started = true;
+            self.restyle();
+        }
+        self.text.push_str(text);
+        // anything styled inside turns everything off at its end, so turn ours back on
+        if self.open && text.contains(Style::reset) {
+            self.text.push_str(&self.style());
+        }
+    }
+
+    // the escapes for whatever's on right now
+    fn style(&self) -> String {
+        let mut style = self.base.start();
+        if self.bold {
+            style.push_str(&self.layout.theme.bold.start());
+        }
+        if self.italic {
+            style.push_str(&self.layout.theme.italic.start());
+        }
+        return style;
+    }
+
+    fn restyle(&mut self) {
+        if !self.started {
+            return;
+        }
+        if self.layout.format == Format::Terminal {
+            if self.open {
+                self.text.push_str(Style::reset);
             }
+            let style = self.style();
+            self.open = !style.is_empty();
+            self.text.push_str(&style);
+            return;
+        }
+        // markup has to nest, so close whatever's open and open whatever's on
+        self.close();
+        if self.bold {
+            self.text.push_str(if self.layout.format == Format::Html { "<b>" } else { "**" });
+        }
+        if self.italic {
+            self.text.push_str(if self.layout.format == Format::Html { "<i>" } else { "*" });
+        }
+        self.shown = (self.bold, self.italic);
+    }
+
+    // closes the markup for bold and italic, innermost first
+    fn close(&mut self) {
+        let (bold, italic) = self.shown;
+        if italic {
+            self.text.push_str(if self.layout.format == Format::Html { "</i>" } else { "*" });
+        }
+        if bold {
+            self.text.push_str(if self.layout.format == Format::Html { "</b>" } else { "**" });
+        }
+        self.shown = (false, false);
+    }
+
+    fn is_empty(&self) -> bool {
+        return !self.started;
+    }
+
+    /// The text, with anything still on turned off
+    fn finish(mut self) -> String {
+        if self.open {
+            self.text.push_str(Style::reset);
+        }
+        self.close();
+        return self.text;
+    }
+}
+
+/// A paragraph, gathered up a node at a time to be wrapped all at once
+struct Paragraph<'a> {
+    /// What it runs on from, like "bank¹"
+    label: Option<String>,
+    inline: Inline<'a>,
+}
+
+impl<'a> Paragraph<'a> {
+    fn new(layout: &'a Layout) -> Paragraph<'a> {
+        return Paragraph { label: None, inline: Inline::new(layout, Style::default()) };
+    }
+
+    // prints what's been gathered so far, if anything
+    fn flush(&mut self) {
+        let layout = self.inline.layout;
+        let inline = std::mem::replace(&mut self.inline, Inline::new(layout, Style::default()));
+        let text = inline.finish();
+        let text = match self.label.take() {
+            Some(label) if text.is_empty() => label,
+            Some(label) => format!("{} — {}", label, text),
+            None => text,
+        };
+        layout.print(&layout.paragraph(&text));
+    }
+}
+
+// 1. for senses, 1.1 for their sub-senses, 1.1.a below that
+fn sense_number(path: &[usize]) -> String {
+    return match path {
+        [] => String::new(),
+        [sense] => format!("{}.", sense),
+        [sense, sub] => format!("{}.{}", sense, sub),
+        [parents @ .., sub] => {
+            let letter = char::from_u32('a' as u32 + (*sub as u32 - 1) % 26).unwrap_or('a');
+            format!("{}.{}", sense_number(parents), letter)
+        },
+    };
+}
+
+// the trees of a descendants list, one under the other
+fn descendants_of(node: &Node, state: &State) -> String {
+    let mut fetch = |title: &str| crate::lookup::lookup(title).ok().flatten().map(|page| page.text);
+    let mut trees = String::new();
+    for mut descendant in descendants::parse(node) {
+        if state.follow {
+            descendant.expand(state.depth, &mut fetch);
+        }
+        trees.push_str(&descendant.to_string());
+    }
+    return trees;
+}
+
+fn superscript(number: usize) -> String {
+    return number.to_string().chars().map(|digit| match digit {
+        '0' => '⁰', '1' => '¹', '2' => '²', '3' => '³', '4' => '⁴',
+        '5' => '⁵', '6' => '⁶', '7' => '⁷', '8' => '⁸', _ => '⁹',
+    }).collect();
+}
+
+// the text of a heading node
+fn value_of<'a>(node: &'a Node<'a>) -> &'a str {
+    if let Node::Heading { nodes, .. } = node {
+        return entry::first_text(nodes).unwrap_or_default();
+    }
+    return "";
+}
+
+/// Wraps a node to display it, intercepting the templates we understand better than the parser does
+struct Rendered<'a>(&'a Node<'a>, &'a Theme);
+
+impl std::fmt::Display for Rendered<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Rendered(node, theme) = self;
+        match node {
+            Node::Template { name, parameters, .. } => {
+                if let Some(form) = FormOf::parse(node) {
+                    return write!(f, "{}", form);
+                }
+                if let Some(ancestor) = Ancestor::parse(node) {
+                    let text = ancestor.to_string();
+                    let rest = text.strip_prefix(ancestor.language()).unwrap_or_default();
+                    return write!(f, "{}{}", theme.etymology_language.paint(ancestor.language()), rest);
+                }
+                let positional = entry::positional(parameters);
+                match entry::first_text(name).unwrap_or_default() {
+                    // {{lb|en|archaic|_|or|dialectal}}
+                    "lb" | "lbl" | "label" | "tlb" | "term-label" => {
+                        let labels = positional.iter().skip(1).filter(|label| *label != "_")
+                            .map(|label| label.as_str()).collect::<Vec<&str>>().join(", ");
+                        return write!(f, "{}", theme.label.paint(&format!("({})", labels.replace(", or,", " or").replace(", and,", " and"))));
+                    },
+                    _ => return write!(f, "{}", node),
+                }
+            },
+            node => return write!(f, "{}", node),
         }
     }
 }
@@ -0,0 +1,158 @@
+use parse_wiki_text::*;
+
+// a structured view over a parsed page, for the things that want more than printing it
+
+/// A single definition line (`#`, or `##` for a sub-sense) under some part of speech
+pub struct Sense<'a> {
+    pub lang: &'a str,
+    pub pos: &'a str,
+    /// Which homograph this is, for words with "Etymology 1", "Etymology 2"...
+    pub etymology: Option<usize>,
+    /// 1 for `#`, 2 for `##`, and so on
+    pub depth: usize,
+    /// The item's own nodes, not including any nested examples or sub-senses
+    pub nodes: Vec<&'a Node<'a>>,
+    /// The `#:` lines under the item: examples, and inline synonyms and such
+    pub details: Vec<&'a [Node<'a>]>,
+}
+
+impl Sense<'_> {
+    pub fn gloss(&self) -> String {
+        return plain_text(self.nodes.iter().copied());
+    }
+}
+
+/// The leading text of a heading or template name, if it has any
+pub fn first_text<'a>(nodes: &'a [Node<'a>]) -> Option<&'a str> {
+    if let Some(Node::Text { value, .. }) = nodes.first() {
+        return Some(value.trim());
+    }
+    return None;
+}
+
+/// The number of a numbered etymology heading: 2 for "Etymology 2"
+pub fn etymology_number(heading: &str) -> Option<usize> {
+    return heading.strip_prefix("Etymology")?.trim().parse().ok();
+}
+
+/// Every sense on the page, tagged with its language, part of speech, and etymology
+pub fn senses<'a>(definition: &'a Output<'a>) -> Vec<Sense<'a>> {
+    let mut senses = Vec::new();
+    let mut lang = "";
+    let mut pos = "";
+    let mut etymology = None;
+    for node in definition.nodes.iter() {
+        match node {
+            Node::Heading { nodes, level, .. } => {
+                if let Some(value) = first_text(nodes) {
+                    if *level == 2 {
+                        lang = value;
+                        etymology = None;
+                    } else if value.starts_with("Etymology") {
+                        etymology = etymology_number(value);
+                    } else {
+                        pos = value;
+                    }
+                }
+            },
+            Node::OrderedList { items, .. } => collect_senses(items, lang, pos, etymology, 1, &mut senses),
+            _ => (),
+        }
+    }
+    return senses;
+}
+
+fn collect_senses<'a>(items: &'a [ListItem<'a>], lang: &'a str, pos: &'a str, etymology: Option<usize>,
+    depth: usize, senses: &mut Vec<Sense<'a>>) {
+    for item in items {
+        let nodes = item.nodes.iter().filter(|node| !is_list(node)).collect();
+        let mut details = Vec::new();
+        for node in &item.nodes {
+            if let Node::DefinitionList { items, .. } = node {
+                details.extend(items.iter().map(|item| item.nodes.as_slice()));
+            }
+        }
+        senses.push(Sense { lang, pos, etymology, depth, nodes, details });
+        for node in &item.nodes {
+            if let Node::OrderedList { items, .. } = node {
+                collect_senses(items, lang, pos, etymology, depth + 1, senses);
+            }
+        }
+    }
+}
+
+fn is_list(node: &Node) -> bool {
+    return matches!(node, Node::OrderedList { .. } | Node::UnorderedList { .. } | Node::DefinitionList { .. });
+}
+
+/// Just the words: link text, the terms inside linking templates, and nothing else.
+/// Good for indexing, not so much for reading.
+pub fn plain_text<'a>(nodes: impl Iterator<Item = &'a Node<'a>>) -> String {
+    let mut buffer = String::new();
+    for node in nodes {
+        match node {
+            Node::Text { value, .. } => buffer.push_str(value),
+            Node::CharacterEntity { character, .. } => buffer.push(*character),
+            Node::Link { text, target, .. } => {
+                if text.is_empty() {
+                    buffer.push_str(target);
+                } else {
+                    buffer.push_str(&plain_text(text.iter()));
+                }
+            },
+            Node::Template { name, parameters, .. } => {
+                let index = match first_text(name) {
+                    Some("l" | "m" | "l-lite" | "ll") => 1,
+                    Some("w" | "gloss" | "gl" | "taxlink") => 0,
+                    _ => continue,
+                };
+                if let Some(parameter) = parameters.iter().filter(|parameter| parameter.name.is_none()).nth(index) {
+                    buffer.push_str(&plain_text(parameter.value.iter()));
+                }
+            },
+            _ => (),
+        }
+    }
+    return buffer.trim().to_string();
+}
+
+/// The contents of every section under `lang` whose heading starts with `name`
+/// ("Pronunciation" also finds "Pronunciation 2"), up to the next heading of any kind
+pub fn sections<'a>(definition: &'a Output<'a>, lang: &str, name: &str) -> Vec<&'a [Node<'a>]> {
+    let mut sections = Vec::new();
+    let mut in_lang = false;
+    let mut start = None;
+    for (i, node) in definition.nodes.iter().enumerate() {
+        if let Node::Heading { nodes, level, .. } = node {
+            if let Some(start) = start.take() {
+                sections.push(&definition.nodes[start..i]);
+            }
+            let value = first_text(nodes).unwrap_or_default();
+            if *level == 2 {
+                in_lang = value == lang;
+            } else if in_lang && value.starts_with(name) {
+                start = Some(i + 1);
+            }
+        }
+    }
+    if let Some(start) = start {
+        sections.push(&definition.nodes[start..]);
+    }
+    return sections;
+}
+
+/// The positional parameters of a template, as plain text
+pub fn positional(parameters: &[Parameter]) -> Vec<String> {
+    return parameters.iter()
+        .filter(|parameter| parameter.name.is_none())
+        .map(|parameter| plain_text(parameter.value.iter()))
+        .collect();
+}
+
+/// A named parameter of a template, as plain text, if present and not empty
+pub fn named(parameters: &[Parameter], key: &str) -> Option<String> {
+    return parameters.iter()
+        .find(|parameter| parameter.name.as_ref().and_then(|name| first_text(name)) == Some(key))
+        .map(|parameter| plain_text(parameter.value.iter()))
+        .filter(|value| !value.is_empty());
+}
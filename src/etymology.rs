@@ -0,0 +1,152 @@
+use parse_wiki_text::*;
+
+use crate::{entry, languages};
+
+// https://en.wiktionary.org/wiki/Wiktionary:Etymology
+// "From {{inh|en|enm|banke}}, from {{der|en|non|bakki}}" is a chain of ancestors, nearest first.
+// each ancestor has its own page (or a Reconstruction: page, for the starred ones),
+// whose etymology usually knows more about what came before it than we do
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kind {
+    Inherited,
+    Borrowed,
+    Derived,
+}
+
+/// A term that the word inherits from, borrows from, or derives from
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ancestor {
+    pub kind: Kind,
+    /// The language code of the source
+    pub lang: String,
+    /// The term, or "" when the template only names the language
+    pub term: String,
+    pub gloss: Option<String>,
+}
+
+impl Ancestor {
+    /// Parses a template node, if it's one of the inherited/borrowed/derived templates
+    pub fn parse(node: &Node) -> Option<Ancestor> {
+        let Node::Template { name, parameters, .. } = node else {
+            return None;
+        };
+        let kind = match entry::first_text(name)? {
+            "inh" | "inh+" | "inh-lite" | "inherited" => Kind::Inherited,
+            "bor" | "bor+" | "borrowed" | "lbor" | "learned borrowing" | "slbor" | "ubor" => Kind::Borrowed,
+            "der" | "der+" | "der-lite" | "derived" | "uder" => Kind::Derived,
+            _ => return None,
+        };
+        // {{inh|target language|source language|term|alt|gloss}}
+        let positional = entry::positional(parameters);
+        let term = positional.get(2).filter(|term| *term != "-").cloned().unwrap_or_default();
+        return Some(Ancestor {
+            kind,
+            lang: positional.get(1)?.clone(),
+            term,
+            gloss: entry::named(parameters, "t").or_else(|| entry::named(parameters, "gloss"))
+                .or_else(|| positional.get(4).filter(|gloss| !gloss.is_empty()).cloned()),
+        });
+    }
+
+    pub fn language(&self) -> &str {
+        return languages::name_or_code(&self.lang);
+    }
+
+    /// The title of the term's own page
+    pub fn page(&self) -> String {
+        return page(&self.term, self.language());
+    }
+}
+
+/// The title of the page for a term in `language`: reconstructed terms live in their own namespace
+pub fn page(term: &str, language: &str) -> String {
+    if let Some(term) = term.strip_prefix('*') {
+        return format!("Reconstruction:{}/{}", language, term);
+    }
+    return term.to_string();
+}
+
+impl std::fmt::Display for Ancestor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.language())?;
+        if !self.term.is_empty() {
+            write!(f, " {}", self.term)?;
+        }
+        if let Some(gloss) = &self.gloss {
+            write!(f, " (“{}”)", gloss)?;
+        }
+        return Ok(());
+    }
+}
+
+/// The ancestor chains of `lang`, one per etymology section, nearest ancestor first
+pub fn chains(definition: &Output, lang: &str) -> Vec<Vec<Ancestor>> {
+    let mut chains = Vec::new();
+    for section in entry::sections(definition, lang, "Etymology") {
+        let chain = section.iter().filter_map(Ancestor::parse).collect::<Vec<Ancestor>>();
+        chains.push(chain);
+    }
+    return chains;
+}
+
+/// A word and the tree of where it came from
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tree {
+    pub label: String,
+    pub children: Vec<Tree>,
+}
+
+/// Follows the etymology of `title` in `lang` back through the pages of its ancestors,
+/// up to `depth` generations. `lookup` fetches the text of a page by title.
+pub fn tree(title: &str, lang: &str, depth: usize, lookup: &mut impl FnMut(&str) -> Option<String>) -> Tree {
+    let mut root = Tree { label: format!("{} ({})", title, lang), children: Vec::new() };
+    if let Some(text) = lookup(title) {
+        let definition = Configuration::default().parse(&text);
+        for chain in chains(&definition, lang) {
+            root.children.extend(branch(&chain, depth, lookup));
+        }
+    }
+    return root;
+}
+
+fn branch(chain: &[Ancestor], depth: usize, lookup: &mut impl FnMut(&str) -> Option<String>) -> Option<Tree> {
+    let (ancestor, rest) = chain.split_first()?;
+    if depth == 0 {
+        return None;
+    }
+    let kind = match ancestor.kind {
+        Kind::Inherited => "inherited",
+        Kind::Borrowed => "borrowed",
+        Kind::Derived => "derived",
+    };
+    let mut tree = Tree { label: format!("{} [{}]", ancestor, kind), children: Vec::new() };
+
+    // prefer what the ancestor's own page says about it, and fall back on the rest of our chain
+    let mut own = Vec::new();
+    if !ancestor.term.is_empty() && let Some(text) = lookup(&ancestor.page()) {
+        let definition = Configuration::default().parse(&text);
+        own = chains(&definition, ancestor.language()).into_iter().find(|chain| !chain.is_empty()).unwrap_or_default();
+    }
+    let next = if own.is_empty() { rest } else { &own };
+    tree.children.extend(branch(next, depth - 1, lookup));
+    return Some(tree);
+}
+
+impl Tree {
+    fn write(&self, f: &mut std::fmt::Formatter<'_>, prefix: &str) -> std::fmt::Result {
+        for (i, child) in self.children.iter().enumerate() {
+            let last = i + 1 == self.children.len();
+            writeln!(f, "{}{}{}", prefix, if last { "└── " } else { "├── " }, child.label)?;
+            child.write(f, &format!("{}{}", prefix, if last { "    " } else { "│   " }))?;
+        }
+        return Ok(());
+    }
+}
+
+impl std::fmt::Display for Tree {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", self.label)?;
+        return self.write(f, "");
+    }
+}
@@ -0,0 +1,247 @@
+use parse_wiki_text::*;
+
+use crate::entry;
+use crate::sorted;
+
+// https://en.wiktionary.org/wiki/Category:Form-of_templates
+// the `* of` templates all say "this word is some form of that word",
+// and `inflection of` says exactly which form through a list of grammatical tags
+
+/// A parsed form-of template: `{{inflection of|la|dīcō||3|s|perf|actv|indc}}`,
+/// `{{past of|en|go}}`, and friends
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormOf {
+    pub lang: String,
+    pub lemma: String,
+    /// Sets of tags, each set describing one form (`inflection of` can list several, split by `;`).
+    /// Abbreviations are expanded, so these read as `["third-person", "singular", "perfect"]`.
+    pub tags: Vec<Vec<String>>,
+    pub gloss: Option<String>,
+}
+
+// https://en.wiktionary.org/wiki/Module:form_of/data
+// not all of them, just the ones that show up constantly
+const tag_names: &[(&str, &str)] = &[
+    ("1", "first-person"), ("2", "second-person"), ("3", "third-person"),
+    ("s", "singular"), ("sg", "singular"), ("d", "dual"), ("du", "dual"), ("p", "plural"), ("pl", "plural"),
+    ("m", "masculine"), ("f", "feminine"), ("n", "neuter"), ("c", "common"),
+    ("nom", "nominative"), ("gen", "genitive"), ("dat", "dative"), ("acc", "accusative"),
+    ("abl", "ablative"), ("voc", "vocative"), ("loc", "locative"), ("ins", "instrumental"),
+    ("pres", "present"), ("past", "past"), ("impf", "imperfect"), ("perf", "perfect"),
+    ("plup", "pluperfect"), ("fut", "future"), ("futr", "future"), ("futp", "future perfect"),
+    ("aor", "aorist"), ("pret", "preterite"), ("cond", "conditional"),
+    ("actv", "active"), ("act", "active"), ("pasv", "passive"), ("pass", "passive"), ("mid", "middle"),
+    ("mp", "mediopassive"),
+    ("indc", "indicative"), ("ind", "indicative"), ("subj", "subjunctive"), ("sub", "subjunctive"),
+    ("impr", "imperative"), ("imp", "imperative"), ("opt", "optative"),
+    ("inf", "infinitive"), ("part", "participle"), ("ptcp", "participle"), ("ger", "gerund"),
+    ("sup", "supine"), ("def", "definite"), ("indef", "indefinite"), ("poss", "possessive"),
+    ("comd", "comparative"), ("comp", "comparative"), ("supd", "superlative"),
+    ("an", "animate"), ("in", "inanimate"), ("pos", "positive"), ("neg", "negative"),
+    ("str", "strong"), ("wk", "weak"), ("mix", "mixed"),
+];
+
+/// Expands a tag abbreviation, including `1//3`-style alternations
+pub fn tag_name(tag: &str) -> String {
+    return tag.split("//").map(|part| {
+        let part = part.trim();
+        return tag_names.iter().find(|(short, _)| *short == part).map_or(part, |(_, long)| long).to_string();
+    }).collect::<Vec<String>>().join("/");
+}
+
+impl FormOf {
+    /// Parses a template node, if it's one of the form-of templates
+    pub fn parse(node: &Node) -> Option<FormOf> {
+        if let Node::Template { name, parameters, .. } = node {
+            return FormOf::from_template(entry::first_text(name)?, parameters);
+        }
+        return None;
+    }
+
+    fn from_template(name: &str, parameters: &[Parameter]) -> Option<FormOf> {
+        let positional = entry::positional(parameters);
+        let gloss = entry::named(parameters, "t").or_else(|| entry::named(parameters, "gloss"));
+
+        match name {
+            // {{inflection of|lang|lemma|alt|tag|tag|...}}
+            "inflection of" | "infl of" => {
+                let mut tags = vec![Vec::new()];
+                for tag in positional.iter().skip(3) {
+                    if tag == ";" {
+                        tags.push(Vec::new());
+                    } else if !tag.is_empty() {
+                        tags.last_mut().unwrap().push(tag_name(tag));
+                    }
+                }
+                tags.retain(|set| !set.is_empty());
+                return Some(FormOf {
+                    lang: positional.first()?.clone(),
+                    lemma: positional.get(1)?.clone(),
+                    tags,
+                    gloss,
+                });
+            },
+            // {{form of|lang|description|lemma|alt|gloss}}: the kind of form is spelled out, rather than in the name
+            "form of" => {
+                let description = positional.get(1).filter(|description| !description.is_empty());
+                return Some(FormOf {
+                    lang: positional.first()?.clone(),
+                    lemma: positional.get(2)?.clone(),
+                    tags: description.map(|description| vec![vec![description.clone()]]).unwrap_or_default(),
+                    gloss: gloss.or_else(|| positional.get(4).filter(|gloss| !gloss.is_empty()).cloned()),
+                });
+            },
+            // the older language-specific ones: {{en-past of|go}}
+            x if x.ends_with(" of") && let Some((lang, kind)) = x.split_once('-')
+            && !lang.contains(' ') && lang.len() <= 3 => {
+                return Some(FormOf {
+                    lang: lang.to_string(),
+                    lemma: positional.first()?.clone(),
+                    tags: vec![vec![kind.trim_end_matches(" of").to_string()]],
+                    gloss,
+                });
+            },
+            // {{past of|lang|lemma|alt|gloss}}
+            x if x.ends_with(" of") => {
+                return Some(FormOf {
+                    lang: positional.first()?.clone(),
+                    lemma: positional.get(1)?.clone(),
+                    tags: vec![vec![x.trim_end_matches(" of").to_string()]],
+                    gloss: gloss.or_else(|| positional.get(3).filter(|gloss| !gloss.is_empty()).cloned()),
+                });
+            },
+            _ => return None,
+        }
+    }
+
+    /// Each tag set as a phrase, e.g. "third-person singular perfect active indicative"
+    pub fn descriptions(&self) -> Vec<String> {
+        return self.tags.iter().map(|set| set.join(" ")).collect();
+    }
+}
+
+impl std::fmt::Display for FormOf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let descriptions = self.descriptions();
+        if descriptions.is_empty() {
+            write!(f, "form of {}", self.lemma)?;
+        } else {
+            write!(f, "{} of {}", descriptions.join(" and "), self.lemma)?;
+        }
+        if let Some(gloss) = &self.gloss {
+            write!(f, " (\"{}\")", gloss)?;
+        }
+        return Ok(());
+    }
+}
+
+/// Every form-of template among the senses of `lang`, in page order
+pub fn form_ofs(definition: &Output, lang: &str) -> Vec<FormOf> {
+    let mut found = Vec::new();
+    for sense in entry::senses(definition) {
+        if sense.lang != lang {
+            continue;
+        }
+        for node in sense.nodes {
+            if let Some(form) = FormOf::parse(node) && !found.contains(&form) {
+                found.push(form);
+            }
+        }
+    }
+    return found;
+}
+
+// the reverse index: from a lemma to all of its forms, and back again
+// each form and tag set gets two lines, one to find it by its lemma and one to find it by the form itself:
+//   L \t lemma \t language \t form \t tags (space-separated)
+//   F \t form \t language \t lemma \t tags
+// all sorted, so that either way round, the lines we want are together and can be binary searched for
+
+/// Collects form-of senses as they come in, and writes them out sorted once we're done
+pub struct Builder {
+    lines: sorted::Writer,
+}
+
+impl Builder {
+    pub fn new(path: &str) -> Builder {
+        return Builder { lines: sorted::Writer::new(path) };
+    }
+
+    pub fn add(&mut self, title: &str, sense: &entry::Sense) -> std::io::Result<()> {
+        let clean = |text: &str| text.replace(['\t', '\n'], " ");
+        for node in sense.nodes.iter() {
+            if let Some(form) = FormOf::parse(node) {
+                for tags in form.tags.iter() {
+                    let (lemma, lang, title, tags) = (clean(&form.lemma), clean(sense.lang), clean(title), clean(&tags.join(" ")));
+                    self.lines.push(format!("L\t{}\t{}\t{}\t{}", lemma, lang, title, tags))?;
+                    self.lines.push(format!("F\t{}\t{}\t{}\t{}", title, lang, lemma, tags))?;
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    pub fn finish(self) -> std::io::Result<()> {
+        return self.lines.finish();
+    }
+}
+
+/// One line of the forms index
+#[derive(Debug, Clone, PartialEq)]
+pub struct Inflection {
+    pub lemma: String,
+    pub lang: String,
+    pub form: String,
+    pub tags: Vec<String>,
+}
+
+/// The forms of a lemma that share a tag set, e.g. all the genitive plurals
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormGroup {
+    pub lang: String,
+    pub tags: Vec<String>,
+    pub forms: Vec<String>,
+}
+
+/// The inflections on the lines for `word`: `L` lines for a lemma, `F` lines for a form
+fn inflections(path: &str, kind: &str, word: &str, lang: Option<&str>)
+    -> Result<Vec<Inflection>, Box<dyn std::error::Error>> {
+    let mut found = Vec::new();
+    for line in sorted::starting_with(path, &format!("{}\t{}\t", kind, word))? {
+        let line = line?;
+        let fields = line.splitn(5, '\t').collect::<Vec<&str>>();
+        let [_, word, language, other, tags] = fields[..] else {
+            return Err("Failed to parse line. Is your forms index valid?".into());
+        };
+        if lang.is_some_and(|lang| lang != language) {
+            continue;
+        }
+        let (lemma, form) = if kind == "L" { (word, other) } else { (other, word) };
+        found.push(Inflection {
+            lemma: lemma.to_string(),
+            lang: language.to_string(),
+            form: form.to_string(),
+            tags: tags.split(' ').filter(|tag| !tag.is_empty()).map(String::from).collect(),
+        });
+    }
+    return Ok(found);
+}
+
+/// Every known form of `lemma`, grouped by tag set, optionally only in `lang`
+pub fn forms(path: &str, lemma: &str, lang: Option<&str>) -> Result<Vec<FormGroup>, Box<dyn std::error::Error>> {
+    let mut groups: Vec<FormGroup> = Vec::new();
+    let found = inflections(path, "L", lemma, lang)?;
+    for inflection in found {
+        if let Some(group) = groups.iter_mut().find(|group| group.lang == inflection.lang && group.tags == inflection.tags) {
+            group.forms.push(inflection.form);
+        } else {
+            groups.push(FormGroup { lang: inflection.lang, tags: inflection.tags, forms: vec![inflection.form] });
+        }
+    }
+    return Ok(groups);
+}
+
+/// Every lemma that `form` is a form of, and which form it is
+pub fn lemmas(path: &str, form: &str, lang: Option<&str>) -> Result<Vec<Inflection>, Box<dyn std::error::Error>> {
+    return inflections(path, "F", form, lang);
+}
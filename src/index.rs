@@ -0,0 +1,36 @@
+use std::{io::*, fs::File};
+use bzip2::bufread::*;
+use parse_wiki_text::Configuration;
+
+use crate::{entry, forms, reverse, titles};
+use crate::page::Pages;
+
+// everything we can't get from the multistream index alone gets built here,
+// in one (long) pass over the whole dump
+
+/// Reads every page of the dump and writes out the search and forms indexes, and sorts the titles
+pub fn build() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    titles::build(File::open(crate::index_path)?, crate::titles_index_path)?;
+
+    let file = File::open(crate::dictionary_path)?;
+    let reader = BufReader::new(MultiBzDecoder::new(BufReader::new(file)));
+    let configuration = Configuration::default();
+
+    let mut search = reverse::Builder::new(crate::search_index_path)?;
+    let mut inflections = forms::Builder::new(crate::forms_index_path);
+    for page in Pages::new(reader) {
+        let page = page?;
+        // only actual entries: no thesaurus, reconstruction, appendix, etc pages
+        if page.ns != 0 {
+            continue;
+        }
+        let definition = configuration.parse(&page.text);
+        for sense in entry::senses(&definition) {
+            search.add(&page.title, &sense)?;
+            inflections.add(&page.title, &sense)?;
+        }
+    }
+    search.finish()?;
+    inflections.finish()?;
+    return Ok(());
+}
@@ -0,0 +1,67 @@
+// https://en.wiktionary.org/wiki/Wiktionary:List_of_languages
+// templates refer to languages by code, headings by name, so we need to go between the two.
+// there are thousands of these; this is the ones that turn up in etymologies and translations a lot
+
+const languages: &[(&str, &str)] = &[
+    // modern languages
+    ("af", "Afrikaans"), ("am", "Amharic"), ("ar", "Arabic"), ("az", "Azerbaijani"),
+    ("be", "Belarusian"), ("bg", "Bulgarian"), ("bn", "Bengali"), ("br", "Breton"),
+    ("ca", "Catalan"), ("cs", "Czech"), ("cy", "Welsh"), ("da", "Danish"), ("de", "German"),
+    ("el", "Greek"), ("en", "English"), ("eo", "Esperanto"), ("es", "Spanish"), ("et", "Estonian"),
+    ("eu", "Basque"), ("fa", "Persian"), ("fi", "Finnish"), ("fo", "Faroese"), ("fr", "French"),
+    ("fy", "West Frisian"), ("ga", "Irish"), ("gd", "Scottish Gaelic"), ("gl", "Galician"),
+    ("gv", "Manx"), ("he", "Hebrew"), ("hi", "Hindi"), ("hr", "Croatian"), ("hu", "Hungarian"),
+    ("hy", "Armenian"), ("id", "Indonesian"), ("is", "Icelandic"), ("it", "Italian"),
+    ("ja", "Japanese"), ("ka", "Georgian"), ("kk", "Kazakh"), ("ko", "Korean"), ("ku", "Kurdish"),
+    ("kw", "Cornish"), ("la", "Latin"), ("lb", "Luxembourgish"), ("lt", "Lithuanian"),
+    ("lv", "Latvian"), ("mi", "Maori"), ("mk", "Macedonian"), ("ml", "Malayalam"),
+    ("mn", "Mongolian"), ("ms", "Malay"), ("mt", "Maltese"), ("nb", "Norwegian Bokmål"),
+    ("nl", "Dutch"), ("nn", "Norwegian Nynorsk"), ("no", "Norwegian"), ("oc", "Occitan"),
+    ("pl", "Polish"), ("pt", "Portuguese"), ("ro", "Romanian"), ("ru", "Russian"),
+    ("sa", "Sanskrit"), ("sco", "Scots"), ("sh", "Serbo-Croatian"), ("sk", "Slovak"),
+    ("sl", "Slovene"), ("sq", "Albanian"), ("sv", "Swedish"), ("sw", "Swahili"), ("ta", "Tamil"),
+    ("te", "Telugu"), ("th", "Thai"), ("tl", "Tagalog"), ("tr", "Turkish"), ("uk", "Ukrainian"),
+    ("ur", "Urdu"), ("uz", "Uzbek"), ("vi", "Vietnamese"), ("yi", "Yiddish"), ("zu", "Zulu"),
+    ("zh", "Chinese"), ("cmn", "Mandarin"), ("yue", "Cantonese"), ("nan", "Min Nan"),
+    ("haw", "Hawaiian"), ("ast", "Asturian"), ("scn", "Sicilian"), ("vec", "Venetian"),
+    ("lad", "Ladino"), ("rm", "Romansch"), ("wa", "Walloon"), ("fur", "Friulian"),
+    ("nds", "Low German"), ("gsw", "Alemannic German"), ("bar", "Bavarian"), ("li", "Limburgish"),
+    ("stq", "Saterland Frisian"), ("frr", "North Frisian"),
+    // historical languages
+    ("ang", "Old English"), ("enm", "Middle English"), ("non", "Old Norse"),
+    ("goh", "Old High German"), ("gmh", "Middle High German"), ("osx", "Old Saxon"),
+    ("gml", "Middle Low German"), ("odt", "Old Dutch"), ("dum", "Middle Dutch"),
+    ("ofs", "Old Frisian"), ("got", "Gothic"), ("fro", "Old French"), ("frm", "Middle French"),
+    ("xno", "Anglo-Norman"), ("pro", "Old Occitan"), ("osp", "Old Spanish"),
+    ("roa-opt", "Old Galician-Portuguese"), ("it-old", "Old Italian"), ("grc", "Ancient Greek"),
+    ("gkm", "Byzantine Greek"), ("la-vul", "Vulgar Latin"), ("la-lat", "Late Latin"),
+    ("la-med", "Medieval Latin"), ("la-new", "New Latin"), ("ML.", "Medieval Latin"),
+    ("LL.", "Late Latin"), ("VL.", "Vulgar Latin"), ("NL.", "New Latin"),
+    ("sga", "Old Irish"), ("mga", "Middle Irish"), ("owl", "Old Welsh"), ("wlm", "Middle Welsh"),
+    ("orv", "Old East Slavic"), ("cu", "Old Church Slavonic"), ("peo", "Old Persian"),
+    ("pal", "Middle Persian"), ("ota", "Ottoman Turkish"), ("arc", "Aramaic"), ("akk", "Akkadian"),
+    ("egy", "Egyptian"), ("hit", "Hittite"), ("txb", "Tocharian B"), ("xtg", "Gaulish"),
+    ("ett", "Etruscan"), ("osc", "Oscan"), ("xum", "Umbrian"), ("pi", "Pali"),
+    // reconstructed languages
+    ("ine-pro", "Proto-Indo-European"), ("gem-pro", "Proto-Germanic"),
+    ("gmw-pro", "Proto-West Germanic"), ("gmq-pro", "Proto-Norse"), ("itc-pro", "Proto-Italic"),
+    ("cel-pro", "Proto-Celtic"), ("grk-pro", "Proto-Hellenic"), ("sla-pro", "Proto-Slavic"),
+    ("bat-pro", "Proto-Baltic"), ("ine-bsl-pro", "Proto-Balto-Slavic"), ("iir-pro", "Proto-Indo-Iranian"),
+    ("ira-pro", "Proto-Iranian"), ("inc-pro", "Proto-Indo-Aryan"), ("urj-pro", "Proto-Uralic"),
+    ("sem-pro", "Proto-Semitic"), ("trk-pro", "Proto-Turkic"), ("roa-pro", "Proto-Romance"),
+];
+
+/// The name of the language with this code, if we know it
+pub fn name(code: &str) -> Option<&'static str> {
+    return languages.iter().find(|(known, _)| *known == code).map(|(_, name)| *name);
+}
+
+/// The code of the language with this name, if we know it
+pub fn code(name: &str) -> Option<&'static str> {
+    return languages.iter().find(|(_, known)| known.eq_ignore_ascii_case(name)).map(|(code, _)| *code);
+}
+
+/// The name if we know it, otherwise the code itself
+pub fn name_or_code(code: &str) -> &str {
+    return name(code).unwrap_or(code);
+}
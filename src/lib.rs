@@ -2,39 +2,356 @@
 #![allow(unused_variables)]
 #![feature(let_chains)]
 
+pub mod config;
 pub mod correct;
+pub mod descendants;
 pub mod display;
+pub mod entry;
+pub mod etymology;
+pub mod forms;
+pub mod index;
+pub mod languages;
 pub mod lookup;
+pub mod markup;
+pub mod page;
+pub mod pager;
+pub mod pronunciation;
+pub mod reverse;
+pub mod sections;
+pub mod sorted;
 pub mod state;
+pub mod theme;
+pub mod thesaurus;
+pub mod titles;
+pub mod translations;
+pub mod tui;
+
+use std::io::{self, Write};
 
 // https://github.com/rust-lang/rfcs/issues/1349
 const version: &str = env!("CARGO_PKG_VERSION");
 const index_path: &str = env!("index_path");
 const dictionary_path: &str = env!("dictionary_path");
-const wiktionary_api_path: &str = "https://en.wiktionary.org/w/api.php?action=parse&format=json&formatversion=2&prop=wikitext&page=";
+const search_index_path: &str = env!("search_index_path");
+const forms_index_path: &str = env!("forms_index_path");
+const titles_index_path: &str = env!("titles_index_path");
+const wiktionary_api_path: &str = "https://en.wiktionary.org/w/api.php?action=query&format=json&formatversion=2&prop=revisions&rvprop=ids|timestamp|content&rvslots=main";
+
+/// Looks up a word and writes out its entry, or what we could find instead.
+/// Fails if the lookup itself does, say without the dump or a connection, or if writing does
+pub fn handle_word(word: String, state: &state::State, out: &mut impl Write) -> Result<(), Box<dyn std::error::Error>> {
+    return handle_word_with(word, state, lookup::lookup, out);
+}
 
-pub fn handle_word(word: String, state: &state::State) {
+/// `handle_word`, finding the word's page with `lookup` rather than in the dump we were built with
+pub fn handle_word_with(word: String, state: &state::State, lookup: impl Fn(&str) -> lookup::Lookup,
+    out: &mut impl Write) -> Result<(), Box<dyn std::error::Error>> {
     // if lets are kinda clunky
-    if let Some(definition) = lookup::lookup(&word).unwrap() {
-        display::display(definition, &state);
+    if let Some(page) = lookup(&word)? {
+        display_page(page, state, out)?;
     } else if let Some(corrected) = correct::correct(&word) {
-        println!("Could not find word {}, continuing with {}...", word, corrected);
-        if let Some(definition) = lookup::lookup(&corrected).unwrap() {
-            display::display(definition, &state);
+        writeln!(out, "Could not find word {}, continuing with {}...", word, corrected)?;
+        if let Some(page) = lookup(corrected)? {
+            display_page(page, state, out)?;
         } else {
-            println!("Could not find corrected word {}.", corrected);
+            writeln!(out, "Could not find corrected word {}.", corrected)?;
         }
     } else {
-        println!("Could not find word {}. Check your spelling?", word);
+        writeln!(out, "Could not find word {}. Check your spelling?", word)?;
     }
+    return Ok(());
+}
+
+/// `handle_word`, but into a string rather than out somewhere
+pub fn word_to_string(word: String, state: &state::State) -> Result<String, Box<dyn std::error::Error>> {
+    let mut out = Vec::new();
+    handle_word(word, state, &mut out)?;
+    return Ok(String::from_utf8_lossy(&out).into_owned());
+}
+
+fn display_page(page: page::Page, state: &state::State, out: &mut impl Write) -> io::Result<()> {
+    if state.verbose {
+        writeln!(out, "{} (page {}): last edited {}, rev {}", page.title, page.id, page.last_edited(), page.revision)?;
+    }
+    if state.thesaurus {
+        return display_thesaurus(&page, state, out);
+    }
+    if !state.translate.is_empty() {
+        return display_translations(&page, state, out);
+    }
+    if state.etymology_tree {
+        return display_etymology_tree(&page, state, out);
+    }
+    let lemmas = if state.lemma { lemmas(&page, state) } else { Vec::new() };
+    display::display(&page.title, &page.text, state, out)?;
+
+    // only one level deep: lemmas are not themselves forms of anything, usually
+    for lemma in lemmas {
+        writeln!(out)?;
+        writeln!(out, "→ {}", lemma)?;
+        match lookup::lookup(&lemma) {
+            Ok(Some(page)) => display::display(&page.title, &page.text, state, out)?,
+            _ => writeln!(out, "Could not find lemma {}.", lemma)?,
+        }
+    }
+    return Ok(());
+}
+
+/// Shows related words instead of definitions, pulling in the thesaurus pages too
+fn display_thesaurus(page: &page::Page, state: &state::State, out: &mut impl Write) -> io::Result<()> {
+    let configuration = parse_wiki_text::Configuration::default();
+    let definition = configuration.parse(&page.text);
+    let Some(lang) = display::displayed_language(&definition, &state.lang) else {
+        return writeln!(out, "Could not find any languages for {}.", page.title);
+    };
+    let mut thesaurus = thesaurus::Thesaurus::parse(&definition, lang);
+
+    // the entry's own thesaurus page, if it has one, and any it points us to
+    let mut references = vec![page.title.clone()];
+    for reference in thesaurus.references.iter() {
+        if !references.contains(reference) {
+            references.push(reference.clone());
+        }
+    }
+    for reference in references {
+        if let Ok(Some(page)) = lookup::lookup(&format!("Thesaurus:{}", reference)) {
+            thesaurus.merge(&configuration.parse(&page.text), lang);
+        }
+    }
+
+    writeln!(out, "{} ({})", page.title, lang)?;
+    if thesaurus.is_empty() {
+        writeln!(out, "Could not find any related words.")?;
+    } else {
+        write!(out, "{}", thesaurus)?;
+    }
+    return Ok(());
+}
+
+/// Shows the translations into the requested languages instead of definitions
+fn display_translations(page: &page::Page, state: &state::State, out: &mut impl Write) -> io::Result<()> {
+    let definition = parse_wiki_text::Configuration::default().parse(&page.text);
+    let Some(lang) = display::displayed_language(&definition, &state.lang) else {
+        return writeln!(out, "Could not find any languages for {}.", page.title);
+    };
+
+    writeln!(out, "{} ({})", page.title, lang)?;
+    let mut found = Vec::new();
+    for table in translations::parse(&definition, lang) {
+        let wanted = table.translations.iter().filter(|translation| translation.is_in(&state.translate)).collect::<Vec<_>>();
+        if wanted.is_empty() {
+            continue;
+        }
+        writeln!(out, "{}", if table.gloss.is_empty() { "(general)" } else { &table.gloss })?;
+        for translation in wanted {
+            writeln!(out, "  {}: {}", translation.lang, translation)?;
+            found.push(translation.clone());
+        }
+    }
+    if found.is_empty() {
+        return writeln!(out, "Could not find any translations into {}.", state.translate.join(", "));
+    }
+
+    // the translations' own entries, in their own languages
+    if state.follow {
+        let mut seen = Vec::new();
+        for translation in found {
+            if seen.contains(&translation.term) {
+                continue;
+            }
+            seen.push(translation.term.clone());
+            writeln!(out)?;
+            writeln!(out, "→ {} ({})", translation.term, translation.lang)?;
+            let state = state::State { lang: translation.lang.clone(), translate: Vec::new(), ..state.clone() };
+            match lookup::lookup(&translation.term) {
+                Ok(Some(page)) => display::display(&page.title, &page.text, &state, out)?,
+                _ => writeln!(out, "Could not find word {}.", translation.term)?,
+            }
+        }
+    }
+    return Ok(());
+}
+
+/// Shows where the word came from, following its ancestors' pages as far as `--depth`
+fn display_etymology_tree(page: &page::Page, state: &state::State, out: &mut impl Write) -> io::Result<()> {
+    let definition = parse_wiki_text::Configuration::default().parse(&page.text);
+    let Some(lang) = display::displayed_language(&definition, &state.lang) else {
+        return writeln!(out, "Could not find any languages for {}.", page.title);
+    };
+    // we already have the page itself, no sense looking it up again
+    let mut fetch = |title: &str| {
+        if title == page.title {
+            return Some(page.text.clone());
+        }
+        return lookup::lookup(title).ok().flatten().map(|page| page.text);
+    };
+    return write!(out, "{}", etymology::tree(&page.title, lang, state.depth, &mut fetch));
+}
+
+/// The lemmas that the displayed language's form-of senses point to
+fn lemmas(page: &page::Page, state: &state::State) -> Vec<String> {
+    let definition = parse_wiki_text::Configuration::default().parse(&page.text);
+    let Some(lang) = display::displayed_language(&definition, &state.lang) else {
+        return Vec::new();
+    };
+    let mut lemmas = Vec::new();
+    for form in forms::form_ofs(&definition, lang) {
+        if form.lemma != page.title && !lemmas.contains(&form.lemma) {
+            lemmas.push(form.lemma);
+        }
+    }
+    return lemmas;
+}
+
+/// Lists the titles starting with `prefix`, restricted to `--lang` if it was given
+pub fn handle_prefix(prefix: &str, state: &state::State, out: &mut impl Write) -> io::Result<()> {
+    let lang = if state.lang_given { Some(state.lang.as_str()) } else { None };
+    let mut written = Ok(());
+    if let Err(error) = titles::prefix(prefix, lang, state.limit, |title| list(out, &mut written, title)) {
+        writeln!(out, "Could not search titles: {}. Prefix search needs the local index.", error)?;
+    }
+    return written;
+}
+
+/// Lists the titles matching a glob (or with `--regex`, a regex), subject to the other filters
+pub fn handle_match(pattern: &str, state: &state::State, out: &mut impl Write) -> io::Result<()> {
+    let pattern = if state.regex {
+        regex::Regex::new(pattern)
+    } else {
+        titles::glob_to_regex(pattern)
+    };
+    let pattern = match pattern {
+        Ok(pattern) => pattern,
+        Err(error) => return writeln!(out, "Invalid pattern: {}", error),
+    };
+    let constraints = titles::Constraints {
+        lang: if state.lang_given { Some(state.lang.as_str()) } else { None },
+        pos: state.pos.as_deref(),
+        length: state.length,
+    };
+    let mut written = Ok(());
+    if let Err(error) = titles::search(|title| pattern.is_match(title), &constraints, state.limit, |title| list(out, &mut written, title)) {
+        writeln!(out, "Could not search titles: {}. Pattern search needs the local index.", error)?;
+    }
+    return written;
+}
+
+// the title searches call back with each title as they find it, and can't be stopped early,
+// so the first error writing one out is kept and the rest are skipped
+fn list(out: &mut impl Write, written: &mut io::Result<()>, title: &str) {
+    if written.is_ok() {
+        *written = writeln!(out, "{}", title);
+    }
+}
+
+/// Finds the words whose definitions best match a description of them
+pub fn handle_search(query: &str, state: &state::State, out: &mut impl Write) -> io::Result<()> {
+    let lang = if state.lang_given { Some(state.lang.as_str()) } else { None };
+    match reverse::search(search_index_path, query, lang, state.pos.as_deref(), state.limit.unwrap_or(10)) {
+        Ok(hits) if hits.is_empty() => writeln!(out, "Could not find any words matching \"{}\".", query)?,
+        Ok(hits) => {
+            for hit in hits {
+                writeln!(out, "{} ({}, {}): {}", hit.title, hit.lang, hit.pos, hit.gloss)?;
+            }
+        },
+        Err(error) => writeln!(out, "Could not search definitions: {}. Did you run --build-index?", error)?,
+    }
+    return Ok(());
+}
+
+/// Lists every form of a lemma, grouped by language and then by what form it is
+pub fn handle_forms(lemma: &str, state: &state::State, out: &mut impl Write) -> io::Result<()> {
+    let lang = if state.lang_given { Some(state.lang.as_str()) } else { None };
+    match forms::forms(forms_index_path, lemma, lang) {
+        Ok(groups) if groups.is_empty() => writeln!(out, "Could not find any forms of {}.", lemma)?,
+        Ok(groups) => {
+            let mut current = "";
+            for group in groups.iter() {
+                if group.lang != current {
+                    writeln!(out, "{}", group.lang)?;
+                    current = &group.lang;
+                }
+                writeln!(out, "  {}: {}", group.tags.join(" "), group.forms.join(", "))?;
+            }
+        },
+        Err(error) => writeln!(out, "Could not look up forms: {}. Did you run --build-index?", error)?,
+    }
+    return Ok(());
 }
 
 // mut state: State, yet state: &mut State?? huh??
-pub fn handle_parameter(word: &str, state: &mut state::State) {
+pub fn handle_parameter(word: &str, args: &mut impl Iterator<Item = String>, state: &mut state::State) {
     match word { // todo: extend
         "--help" => {
             println!("dictionarium {}\n", version);
-            println!("Usage: dictionarium <word>");
+            println!("Usage: dictionarium [--verbose] [--lemma] [--pronunciation] [--thesaurus] [--lang <language>] [--section <section>...] [--pos <part of speech>] [--etym <n>] [--width <n>] [--color always|never|auto] [--format terminal|markdown|html] [--links] [--no-pager] <word>");
+            println!("       dictionarium [--lang <language>] [--limit <n>] --prefix <prefix>");
+            println!("       dictionarium [--lang <language>] [--pos <part of speech>] [--length <n>] [--limit <n>] [--regex] --match <pattern>");
+            println!("       dictionarium [--lang <language>] [--pos <part of speech>] [--limit <n>] --search <description>");
+            println!("       dictionarium [--lang <language>] [--follow] --translate <language> [--translate <language>...] <word>");
+            println!("       dictionarium [--lang <language>] [--depth <n>] --etymology-tree <word>");
+            println!("       dictionarium [--lang <language>] --forms <lemma>");
+            println!("       dictionarium tui [<word>]");
+            println!("       dictionarium --build-index");
+        },
+        "--verbose" => state.verbose = true,
+        "--lemma" => state.lemma = true,
+        "--pronunciation" => state.pronunciation = true,
+        "--thesaurus" => state.thesaurus = true,
+        "--translate" => state.translate.extend(args.next()),
+        "--follow" => state.follow = true,
+        "--etymology-tree" => state.etymology_tree = true,
+        "--depth" => state.depth = args.next().and_then(|depth| depth.parse().ok()).unwrap_or(state.depth),
+        "--lang" => {
+            if let Some(lang) = args.next() {
+                state.lang = lang;
+                state.lang_given = true;
+            } else {
+                println!("Missing language for \"--lang\".");
+            }
+        },
+        "--prefix" => state.prefix = args.next(),
+        "--match" => state.pattern = args.next(),
+        "--regex" => state.regex = true,
+        "--search" => state.query = args.next(),
+        "--forms" => state.forms = args.next(),
+        "--build-index" => {
+            println!("Building indexes from {}. This reads the whole dump, so it'll be a while...", dictionary_path);
+            match index::build() {
+                Ok(()) => println!("Done."),
+                Err(error) => println!("Could not build indexes: {}", error),
+            }
+        },
+        "--pos" => state.pos = args.next(),
+        "--etym" => state.etymology = args.next().and_then(|number| number.parse().ok()),
+        "--section" => {
+            if let Some(section) = args.next() {
+                // pronunciation gets its own compact rendering
+                if section.eq_ignore_ascii_case("pronunciation") {
+                    state.pronunciation = true;
+                }
+                state.sections.push(section);
+            }
+        },
+        "--length" => state.length = args.next().and_then(|length| length.parse().ok()),
+        "--limit" => state.limit = args.next().and_then(|limit| limit.parse().ok()),
+        "--color" => match args.next().as_deref().and_then(theme::Color::parse) {
+            Some(color) => state.color = color,
+            None => println!("Expected always, never or auto for \"--color\"."),
+        },
+        "--links" => state.links = true,
+        "--no-pager" => state.pager = false,
+        "--format" => match args.next().as_deref().and_then(markup::Format::parse) {
+            Some(format) => state.format = format,
+            None => println!("Expected terminal, markdown or html for \"--format\"."),
+        },
+        "--width" => state.width = args.next().and_then(|width| width.parse().ok()),
+        // for shell completion scripts: one candidate per line, nothing else
+        "--complete" => {
+            for title in titles::complete(&args.next().unwrap_or_default(), 50) {
+                println!("{}", title);
+            }
+            std::process::exit(0);
         },
         _ => {
             println!("Unknown flag \"{}\".", word);
@@ -1,13 +1,15 @@
-use std::{io::*, fs::File};
+use std::{io::*, fs::File, path::Path};
 // note that bufread::MultiBzDecoder is _distinct_ from read::MultiBzDecoder
 use bzip2::bufread::*;
 
+use crate::page::{Page, Pages};
+use crate::titles;
 use crate::wiktionary_api_path;
 
 // i don't like that there are multiple result types
 // that seems Bad
 // also having to explicitly box dyn Error sucks, fine fuck you it's the rust way
-type Lookup = std::result::Result<Option<String>, Box<dyn std::error::Error>>;
+pub type Lookup = std::result::Result<Option<Page>, Box<dyn std::error::Error>>;
 
 // WHY can you not implement traits on external types, like what??
 // fortunately we needed to copy-paste the parse_wiki_text library to fix some bugs anyhow
@@ -19,55 +21,62 @@ pub fn lookup(word: &str) -> Lookup {
     }
 }
 
-fn lookup_local(word: &str, file: File) -> Lookup {
-    let reader = BufReader::new(MultiBzDecoder::new(BufReader::new(file)));
-    for line in reader.lines() {
-        let line = line?;
+pub fn lookup_local(word: &str, file: File) -> Lookup {
+    return lookup_local_in(word, file, crate::dictionary_path);
+}
 
-        // format: file-offset:page-id:page-title
-        let line = line.splitn(3, ":").collect::<Vec<&str>>();
-        assert!(line.len() == 3, "Failed to parse line. Is your index file valid?");
+/// `lookup_local`, in a dump other than the one we were built with
+pub fn lookup_local_in(word: &str, index: File, dictionary: impl AsRef<Path>) -> Lookup {
+    for entry in titles::entries(index) {
+        let entry = entry?;
+        if entry.title == word {
+            // the page id is less fragile than the title, so match on that
+            let page = read_block_from(&dictionary, entry.offset)?.into_iter().find(|page| page.id == entry.id);
+            return Ok(page);
+        }
+    }
+    return Ok(None);
+}
 
-        let offset = line.get(0).unwrap().parse::<u64>()?;
-        let id = line.get(1).unwrap().parse::<u64>()?;
-        let title = *line.get(2).unwrap(); // this dereference now makes sense
+/// Decompresses the chunk of the dump starting at `offset`.
+/// Note: our chunk contains multiple pages, usually a hundred.
+pub fn read_block(offset: u64) -> std::result::Result<Vec<Page>, Box<dyn std::error::Error>> {
+    return read_block_from(crate::dictionary_path, offset);
+}
 
-        if title == word {
-            let file = File::open(crate::dictionary_path)?;
-            let mut reader = BufReader::new(file);
+/// `read_block`, from a dump other than the one we were built with
+pub fn read_block_from(dictionary: impl AsRef<Path>, offset: u64) -> std::result::Result<Vec<Page>, Box<dyn std::error::Error>> {
+    let file = File::open(dictionary)?;
+    let mut reader = BufReader::new(file);
 
-            // note: our chunk contains multiple pages
-            let offset = reader.seek(SeekFrom::Start(offset))?;
-            let reader = BufReader::new(BzDecoder::new(reader));
+    reader.seek(SeekFrom::Start(offset))?;
+    let reader = BufReader::new(BzDecoder::new(reader));
 
-            let mut buffer = String::new();
-            let mut page = false;
-            for line in reader.lines() {
-                let line = line.unwrap();
-                if line == format!("    <title>{}</title>", title) {
-                    buffer.push_str("  <page>");
-                    buffer.push_str("\n");
-                    page = true;
-                }
-                if page {
-                    buffer.push_str(&line);
-                    buffer.push_str("\n");
-                    if line == "  </page>" {
-                        break;
-                    }
-                }
-            }
-            return Ok(Some(buffer));
-        }
+    let mut pages = Vec::new();
+    for page in Pages::new(reader) {
+        pages.push(page?);
     }
-    return Ok(None);
+    return Ok(pages);
 }
 
 // holy shit this is compact
-fn lookup_online(word: &str) -> Lookup {
-    let response = reqwest::blocking::get(wiktionary_api_path.to_owned() + word)?.json::<serde_json::Value>()?;
-    if let Some(serde_json::Value::String(wikitext)) = response.get("parse").and_then(|value| value.get("wikitext")) {
-        return Ok(Some(String::from(wikitext)));
+// (less so now that we ask for the revision metadata too)
+pub fn lookup_online(word: &str) -> Lookup {
+    // titles like AT&T need escaping, so let reqwest build the query string
+    let response = reqwest::blocking::Client::new().get(wiktionary_api_path)
+        .query(&[("titles", word)]).send()?.json::<serde_json::Value>()?;
+    let page = response.get("query").and_then(|value| value.get("pages")).and_then(|value| value.get(0));
+    let revision = page.and_then(|value| value.get("revisions")).and_then(|value| value.get(0));
+    if let Some(page) = page && let Some(revision) = revision
+    && let Some(serde_json::Value::String(wikitext)) = revision.pointer("/slots/main/content") {
+        return Ok(Some(Page {
+            title: page.get("title").and_then(|value| value.as_str()).unwrap_or(word).to_string(),
+            ns: page.get("ns").and_then(|value| value.as_i64()).unwrap_or_default() as i32,
+            id: page.get("pageid").and_then(|value| value.as_u64()).unwrap_or_default(),
+            revision: revision.get("revid").and_then(|value| value.as_u64()).unwrap_or_default(),
+            timestamp: revision.get("timestamp").and_then(|value| value.as_str()).unwrap_or_default().to_string(),
+            text: String::from(wikitext),
+        }));
     } else {
         return Ok(None);
     }
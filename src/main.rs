@@ -1,25 +1,77 @@
 use dictionarium::*;
+use std::io::Write;
 
 fn main() {
     let mut state = state::State::new();
+    config::load(&mut state);
     let args: Vec<String> = std::env::args().skip(1).collect();
 
-    if args.len() == 0 {
-        dictionarium::handle_parameter("--help", &mut state);
+    if args.is_empty() {
+        dictionarium::handle_parameter("--help", &mut std::iter::empty(), &mut state);
         std::process::exit(0);
     }
 
+    // a browser instead of a lookup. only as the very first argument, so the bird can still be looked up
+    let browse = args[0] == "tui";
+    let skip = usize::from(browse);
+
+    // flags may take a value, so they get to pull from the same iterator
     let mut words = Vec::<String>::new();
-    for word in args {
+    let mut args = args.into_iter().skip(skip);
+    while let Some(word) = args.next() {
         if word.get(0..2) == Some("--") {
-            dictionarium::handle_parameter(&word, &mut state);
+            dictionarium::handle_parameter(&word, &mut args, &mut state);
         } else {
             words.push(word);
         }
     }
 
-    // we accept multiple words gladly
-    for word in words {
-        dictionarium::handle_word(word, &state);
+    if browse {
+        if let Err(error) = tui::run(words.into_iter().next(), &state) {
+            println!("Could not run the browser: {}", error);
+        }
+        return;
+    }
+
+    // colour, hyperlinks and width depend on where we're writing to, so they're settled here, once
+    state.resolve();
+
+    // lists of titles can go on for a long time, so they're written out as they're found.
+    // if stdout went away (like when piped into `head`), there's nobody left to tell
+    if list(&state, &mut std::io::stdout().lock()).is_err() {
+        return;
+    }
+
+    // we accept multiple words gladly, and page them all together
+    let mut output = Vec::new();
+    for word in words.iter() {
+        // in with the rest of the output, so it comes out in order
+        if let Err(error) = dictionarium::handle_word(word.clone(), &state, &mut output) {
+            let _ = writeln!(output, "Could not look up {}: {}", word, error);
+        }
+    }
+    // each entry is only an <article>, so they all go in the one page
+    if state.format == markup::Format::Html && !words.is_empty() {
+        output = markup::document(&words.join(", "), &String::from_utf8_lossy(&output)).into_bytes();
+    }
+    if let Err(error) = pager::show(&output, &state) {
+        println!("Could not write out the entry: {}", error);
+    }
+}
+
+/// Does whichever of the searches were asked for
+fn list(state: &state::State, out: &mut impl Write) -> std::io::Result<()> {
+    if let Some(prefix) = &state.prefix {
+        dictionarium::handle_prefix(prefix, state, out)?;
+    }
+    if let Some(pattern) = &state.pattern {
+        dictionarium::handle_match(pattern, state, out)?;
+    }
+    if let Some(query) = &state.query {
+        dictionarium::handle_search(query, state, out)?;
+    }
+    if let Some(lemma) = &state.forms {
+        dictionarium::handle_forms(lemma, state, out)?;
     }
+    return Ok(());
 }
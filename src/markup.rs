@@ -0,0 +1,91 @@
+// besides text for the terminal, entries can be written out as markdown or html.
+// the walk over the sections is the same for all of them: only how each piece gets written differs
+
+/// What to write entries out as, from `--format` or the `format` config option
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Terminal,
+    Markdown,
+    Html,
+}
+
+impl Format {
+    pub fn parse(value: &str) -> Option<Format> {
+        return match value {
+            "terminal" | "text" => Some(Format::Terminal),
+            "markdown" | "md" => Some(Format::Markdown),
+            "html" => Some(Format::Html),
+            _ => None,
+        };
+    }
+}
+
+/// Escapes text so that it comes out as itself, rather than as markup
+pub fn escape(format: Format, text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match (format, c) {
+            (Format::Markdown, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '|' | '#') => {
+                escaped.push('\\');
+                escaped.push(c);
+            },
+            (Format::Html, '&') => escaped.push_str("&amp;"),
+            (Format::Html, '<') => escaped.push_str("&lt;"),
+            (Format::Html, '>') => escaped.push_str("&gt;"),
+            (Format::Html, '"') => escaped.push_str("&quot;"),
+            (Format::Html, '\'') => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    return escaped;
+}
+
+/// A whole html page around `body`, the entries' `<article>`s
+pub fn document(title: &str, body: &str) -> String {
+    return format!("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape(Format::Html, title), body);
+}
+
+/// Where links go by default, with `{}` standing in for the title
+pub const default_link: &str = "https://en.wiktionary.org/wiki/{}";
+
+/// Where a wiki link points: `pattern` filled in with its page, and any `#Section` on the end kept as an anchor.
+/// Links within the page, like `[[#English]]`, are just the anchor.
+pub fn url(pattern: &str, target: &str) -> String {
+    let (page, section) = match target.split_once('#') {
+        Some((page, section)) => (page, Some(section)),
+        None => (target, None),
+    };
+    let mut url = String::new();
+    if !page.trim().is_empty() {
+        url.push_str(&pattern.replace("{}", &encode(page)));
+    }
+    if let Some(section) = section {
+        url.push('#');
+        url.push_str(&anchor(section));
+    }
+    return url;
+}
+
+/// The id of a heading (or an entry), the way Wiktionary does them: `Etymology_1`
+pub fn anchor(heading: &str) -> String {
+    return encode(heading);
+}
+
+// spaces become underscores, as in Wiktionary's own URLs,
+// and anything that would end the URL early (in markdown or html) gets percent-encoded
+fn encode(title: &str) -> String {
+    let mut encoded = String::new();
+    for c in title.trim().chars() {
+        match c {
+            ' ' => encoded.push('_'),
+            '"' | '\'' | '%' | '<' | '>' | '?' | '#' | '(' | ')' | '[' | ']' | '{' | '}' | '|' | '\\' | '^' | '`' => {
+                for byte in c.to_string().bytes() {
+                    encoded.push_str(&format!("%{:02X}", byte));
+                }
+            },
+            c => encoded.push(c),
+        }
+    }
+    return encoded;
+}
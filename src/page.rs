@@ -0,0 +1,134 @@
+use std::io::BufRead;
+use quick_xml::{Reader, events::Event};
+
+/// A single page of the dump, with the xml stripped away and the text unescaped
+#[derive(Debug, Clone, Default)]
+pub struct Page {
+    pub title: String,
+    pub ns: i32,
+    pub id: u64,
+    pub revision: u64,
+    pub timestamp: String,
+    pub text: String,
+}
+
+impl Page {
+    /// The date of the last revision, without the time
+    pub fn last_edited(&self) -> &str {
+        return self.timestamp.split('T').next().unwrap_or_default();
+    }
+
+    /// Whether the page has a level two heading for `lang`
+    pub fn has_language(&self, lang: &str) -> bool {
+        return self.text.lines().any(|line| {
+            let line = line.trim();
+            return line.starts_with("==") && !line.starts_with("===")
+                && line.trim_matches('=').trim() == lang;
+        });
+    }
+
+    /// Whether the page has a heading for `pos` (at any depth, for nested etymologies),
+    /// and inside the section for `lang` if one is given
+    pub fn has_part_of_speech(&self, lang: Option<&str>, pos: &str) -> bool {
+        let mut in_lang = lang.is_none();
+        for line in self.text.lines() {
+            let line = line.trim();
+            if !line.starts_with("==") {
+                continue;
+            }
+            let heading = line.trim_matches('=').trim();
+            if !line.starts_with("===") {
+                in_lang = lang.is_none_or(|lang| heading == lang);
+            } else if in_lang && heading.eq_ignore_ascii_case(pos) {
+                return true;
+            }
+        }
+        return false;
+    }
+
+    /// Whether `other` (say, the live page) has been edited since this copy was taken
+    pub fn is_older_than(&self, other: &Page) -> bool {
+        return self.revision < other.revision;
+    }
+}
+
+// which element's text we're currently collecting
+#[derive(PartialEq)]
+enum Field { Title, Ns, Id, Revision, Timestamp, Text, None }
+
+/// Streams pages out of a chunk of the dump.
+/// Each bz2 stream of the multistream archive is a bare run of `<page>` elements
+/// (the first and last also carry the `<mediawiki>` open and close), so we don't expect a root.
+pub struct Pages<R: BufRead> {
+    reader: Reader<R>,
+    buffer: Vec<u8>,
+}
+
+impl<R: BufRead> Pages<R> {
+    pub fn new(reader: R) -> Pages<R> {
+        let mut reader = Reader::from_reader(reader);
+        // the chunks are fragments, so the tags don't necessarily balance
+        reader.config_mut().check_end_names = false;
+        return Pages { reader, buffer: Vec::new() };
+    }
+
+    fn next_page(&mut self) -> Result<Option<Page>, quick_xml::Error> {
+        let mut page: Option<Page> = None;
+        let mut field = Field::None;
+        // the page, revision, and contributor ids all share a tag name, so track where we are
+        let mut in_revision = false;
+        let mut in_contributor = false;
+        let mut value = String::new();
+        loop {
+            self.buffer.clear();
+            match self.reader.read_event_into(&mut self.buffer)? {
+                Event::Start(tag) => match tag.name().as_ref() {
+                    b"page" => page = Some(Page::default()),
+                    b"revision" => in_revision = true,
+                    b"title" => field = Field::Title,
+                    b"ns" => field = Field::Ns,
+                    b"id" if !in_revision => field = Field::Id,
+                    b"id" if in_revision && !in_contributor => field = Field::Revision,
+                    b"contributor" => in_contributor = true,
+                    b"timestamp" if in_revision => field = Field::Timestamp,
+                    b"text" => field = Field::Text,
+                    _ => field = Field::None,
+                },
+                Event::Text(text) if field != Field::None => {
+                    value.push_str(&text.unescape()?);
+                },
+                Event::End(tag) => {
+                    if let Some(page) = page.as_mut() {
+                        let value = std::mem::take(&mut value);
+                        match field {
+                            Field::Title => page.title = value,
+                            Field::Ns => page.ns = value.trim().parse().unwrap_or_default(),
+                            Field::Id => page.id = value.trim().parse().unwrap_or_default(),
+                            Field::Revision => page.revision = value.trim().parse().unwrap_or_default(),
+                            Field::Timestamp => page.timestamp = value,
+                            Field::Text => page.text = value,
+                            Field::None => (),
+                        }
+                    }
+                    field = Field::None;
+                    match tag.name().as_ref() {
+                        b"revision" => in_revision = false,
+                        b"contributor" => in_contributor = false,
+                        b"page" => return Ok(page),
+                        _ => (),
+                    }
+                },
+                Event::Eof => return Ok(None),
+                _ => (),
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for Pages<R> {
+    type Item = Result<Page, quick_xml::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        return self.next_page().transpose();
+    }
+}
@@ -0,0 +1,55 @@
+use std::io::{self, IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+use crate::state::State;
+
+// long entries scroll right off the screen, so they go through a pager like git's do.
+// only when there's a screen to scroll off of: piped output is left alone
+
+/// The pager to use when `$PAGER` isn't set. `-R` lets our colours through
+const default_pager: &str = "less -R";
+
+/// Writes out `output`, through `$PAGER` if it won't fit on the screen
+pub fn show(output: &[u8], state: &State) -> io::Result<()> {
+    if state.pager && io::stdout().is_terminal()
+        && let Some((terminal_size::Width(width), terminal_size::Height(height))) = terminal_size::terminal_size()
+        && lines(output, width as usize) > height as usize
+        && let Some(mut pager) = spawn() {
+        if let Some(mut stdin) = pager.stdin.take() {
+            // quitting the pager early closes the pipe, which is fine
+            match stdin.write_all(output) {
+                Err(error) if error.kind() != io::ErrorKind::BrokenPipe => return Err(error),
+                _ => {},
+            }
+        }
+        pager.wait()?;
+        return Ok(());
+    }
+    return io::stdout().write_all(output);
+}
+
+/// Starts the pager, if there is one to start
+fn spawn() -> Option<std::process::Child> {
+    let pager = std::env::var("PAGER").ok().filter(|pager| !pager.trim().is_empty()).unwrap_or(default_pager.to_string());
+    let mut words = pager.split_whitespace();
+    let program = words.next()?;
+    // `cat` and friends page nothing, no sense in starting them up
+    if program == "cat" {
+        return None;
+    }
+    let mut command = Command::new(program);
+    command.args(words).stdin(Stdio::piped());
+    // unless someone has told less otherwise: quit if it all fits (F), pass the colours through (R), and leave the entry on screen after (X)
+    if std::env::var_os("LESS").is_none() {
+        command.env("LESS", "FRX");
+    }
+    return command.spawn().ok();
+}
+
+/// How many rows `output` takes up on a screen `width` columns wide, wrapping included
+fn lines(output: &[u8], width: usize) -> usize {
+    let width = width.max(1);
+    return String::from_utf8_lossy(output).lines()
+        .map(|line| textwrap::core::display_width(line).div_ceil(width).max(1))
+        .sum();
+}
@@ -0,0 +1,129 @@
+use parse_wiki_text::*;
+
+use crate::entry;
+
+// https://en.wiktionary.org/wiki/Wiktionary:Pronunciation
+// a pronunciation section is a bulleted list of templates, with accent qualifiers ({{a|UK}})
+// at the start of a line applying to everything after them on that line
+
+/// A set of IPA transcriptions, and the accents they're for (if any were given)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Ipa {
+    pub accents: Vec<String>,
+    pub transcriptions: Vec<String>,
+}
+
+/// Everything from the pronunciation section(s) of one language
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Pronunciation {
+    pub ipa: Vec<Ipa>,
+    /// What the language-specific generators ({{la-IPA|dīcō}}) would work the IPA out from
+    pub respellings: Vec<String>,
+    /// English only: the dictionary-style respelling
+    pub enpr: Vec<String>,
+    pub rhymes: Vec<String>,
+    /// Each a list of syllables
+    pub hyphenation: Vec<Vec<String>>,
+    pub homophones: Vec<String>,
+    /// File names, as on Wikimedia Commons
+    pub audio: Vec<String>,
+}
+
+impl Pronunciation {
+    /// Collects the pronunciation sections under `lang`, if it has any
+    pub fn parse(definition: &Output, lang: &str) -> Option<Pronunciation> {
+        let mut pronunciation = Pronunciation::default();
+        for section in entry::sections(definition, lang, "Pronunciation") {
+            for node in section {
+                pronunciation.collect_list(node);
+            }
+        }
+        if pronunciation == Pronunciation::default() {
+            return None;
+        }
+        return Some(pronunciation);
+    }
+
+    fn collect_list(&mut self, node: &Node) {
+        if let Node::UnorderedList { items, .. } = node {
+            for item in items {
+                self.collect_line(&item.nodes);
+            }
+        }
+    }
+
+    fn collect_line(&mut self, nodes: &[Node]) {
+        let mut accents = Vec::new();
+        for node in nodes {
+            let Node::Template { name, parameters, .. } = node else {
+                // sub-bullets, usually accents nested under a broader one
+                self.collect_list(node);
+                continue;
+            };
+            let positional = entry::positional(parameters);
+            match entry::first_text(name).unwrap_or_default() {
+                "a" | "accent" => accents = positional,
+                "IPA" => {
+                    let mut accents = accents.clone();
+                    accents.extend(entry::named(parameters, "a"));
+                    let transcriptions = positional.into_iter().skip(1).filter(|ipa| !ipa.is_empty()).collect();
+                    self.ipa.push(Ipa { accents, transcriptions });
+                },
+                // the language-specific generators ({{la-IPA|dīcō}}) compute the IPA from a respelling,
+                // which we can't do. the respelling is better than nothing, so long as it's not passed off as IPA
+                x if x.ends_with("-IPA") || x.ends_with("-pr") => {
+                    self.respellings.extend(positional.into_iter().next().filter(|word| !word.is_empty()));
+                },
+                "enPR" => self.enpr.extend(positional.into_iter().filter(|word| !word.is_empty())),
+                "rhymes" | "rhyme" => self.rhymes.extend(positional.into_iter().skip(1).filter(|rhyme| !rhyme.is_empty())),
+                "hyphenation" | "hyph" => {
+                    let syllables = positional.into_iter().skip(1).filter(|syllable| !syllable.is_empty()).collect::<Vec<String>>();
+                    if !syllables.is_empty() {
+                        self.hyphenation.push(syllables);
+                    }
+                },
+                "homophones" | "homophone" | "hmp" => self.homophones.extend(positional.into_iter().skip(1).filter(|word| !word.is_empty())),
+                "audio" => self.audio.extend(positional.into_iter().nth(1).filter(|file| !file.is_empty())),
+                _ => (),
+            }
+        }
+    }
+}
+
+// all on one line, since it's going under the headword
+impl std::fmt::Display for Pronunciation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        if !self.ipa.is_empty() {
+            let ipa = self.ipa.iter().filter(|ipa| !ipa.transcriptions.is_empty()).map(|ipa| {
+                if ipa.accents.is_empty() {
+                    return ipa.transcriptions.join(", ");
+                }
+                return format!("{} ({})", ipa.transcriptions.join(", "), ipa.accents.join(", "));
+            }).collect::<Vec<String>>();
+            if !ipa.is_empty() {
+                parts.push(format!("IPA: {}", ipa.join("; ")));
+            }
+        }
+        if !self.respellings.is_empty() {
+            parts.push(format!("Respelling: {}", self.respellings.join(", ")));
+        }
+        if !self.enpr.is_empty() {
+            parts.push(format!("enPR: {}", self.enpr.join(", ")));
+        }
+        if !self.rhymes.is_empty() {
+            parts.push(format!("Rhymes: -{}", self.rhymes.join(", -")));
+        }
+        if !self.hyphenation.is_empty() {
+            let hyphenation = self.hyphenation.iter().map(|syllables| syllables.join("‧")).collect::<Vec<String>>();
+            parts.push(format!("Hyphenation: {}", hyphenation.join(", ")));
+        }
+        if !self.homophones.is_empty() {
+            parts.push(format!("Homophones: {}", self.homophones.join(", ")));
+        }
+        if !self.audio.is_empty() {
+            parts.push(format!("Audio: {}", self.audio.join(", ")));
+        }
+        return write!(f, "{}", parts.join(" · "));
+    }
+}
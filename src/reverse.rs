@@ -0,0 +1,168 @@
+use std::{io::*, fs::File, collections::HashMap};
+
+use crate::entry::Sense;
+use crate::sorted;
+
+// a reverse dictionary: from what a word means, back to the word
+// the index is a plain text file: a header, then documents (one per sense), then postings (one per term per sense):
+//   N \t documents \t total length \t where the postings start
+//   D \t length \t title \t language \t part of speech \t gloss
+//   term \t document offset \t document length \t frequency
+// the postings are sorted by term, so a query binary searches for its own terms and reads only those,
+// and they point at their documents by offset, so the ones that score can be read straight off
+
+type Error = Box<dyn std::error::Error>;
+
+// bm25 tuning, the usual values
+const k1: f64 = 1.2;
+const b: f64 = 0.75;
+
+const stopwords: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "by", "for", "from", "in", "into", "is", "it",
+    "its", "of", "on", "or", "that", "the", "this", "to", "with",
+];
+
+/// Lowercased words, minus the ones that show up in every other definition
+pub fn tokenise(text: &str) -> Vec<String> {
+    return text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .filter(|word| !stopwords.contains(&word.as_str()))
+        .collect();
+}
+
+/// How much room the header gets. It's written last, over the top of this many spaces
+const header_width: usize = 63;
+
+/// Writes the index as senses come in. Documents go straight to disk; postings get sorted, spilling to disk as they go
+pub struct Builder {
+    writer: BufWriter<File>,
+    postings: sorted::Writer,
+    documents: u64,
+    total_length: u64,
+    /// Where the next document goes
+    offset: u64,
+}
+
+impl Builder {
+    pub fn new(path: &str) -> std::result::Result<Builder, Error> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "{:1$}", "", header_width)?;
+        return Ok(Builder {
+            writer,
+            postings: sorted::Writer::new(format!("{}.postings", path)),
+            documents: 0,
+            total_length: 0,
+            offset: header_width as u64 + 1,
+        });
+    }
+
+    pub fn add(&mut self, title: &str, sense: &Sense) -> std::result::Result<(), Error> {
+        let gloss = sense.gloss();
+        let tokens = tokenise(&gloss);
+        if tokens.is_empty() {
+            return Ok(());
+        }
+
+        let mut frequencies = HashMap::<&str, u32>::new();
+        for token in tokens.iter() {
+            *frequencies.entry(token).or_default() += 1;
+        }
+        for (token, frequency) in frequencies {
+            self.postings.push(format!("{}\t{}\t{}\t{}", token, self.offset, tokens.len(), frequency))?;
+        }
+
+        // tabs and newlines would break the format, and nobody will miss them
+        let clean = |text: &str| text.replace(['\t', '\n'], " ");
+        let document = format!("D\t{}\t{}\t{}\t{}\t{}", tokens.len(),
+            clean(title), clean(sense.lang), clean(sense.pos), clean(&gloss));
+        writeln!(self.writer, "{}", document)?;
+        self.offset += document.len() as u64 + 1;
+        self.documents += 1;
+        self.total_length += tokens.len() as u64;
+        return Ok(());
+    }
+
+    pub fn finish(mut self) -> std::result::Result<(), Error> {
+        for posting in self.postings.sorted()? {
+            writeln!(self.writer, "{}", posting?)?;
+        }
+        // now that we know what goes in it
+        let header = format!("N\t{}\t{}\t{}", self.documents, self.total_length, self.offset);
+        self.writer.seek(SeekFrom::Start(0))?;
+        write!(self.writer, "{:1$}", header, header_width)?;
+        self.writer.flush()?;
+        return Ok(());
+    }
+}
+
+/// A sense whose gloss matched the query
+#[derive(Debug, Clone)]
+pub struct Hit {
+    pub title: String,
+    pub lang: String,
+    pub pos: String,
+    pub gloss: String,
+    pub score: f64,
+}
+
+/// Ranks every sense against `query` with bm25, best first, optionally only within a language / part of speech
+pub fn search(path: &str, query: &str, lang: Option<&str>, pos: Option<&str>, limit: usize)
+    -> std::result::Result<Vec<Hit>, Error> {
+    let mut query = tokenise(query);
+    query.sort();
+    query.dedup();
+
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut header = String::new();
+    reader.read_line(&mut header)?;
+    let fields = header.trim_end().split('\t').collect::<Vec<&str>>();
+    let ["N", total, total_length, postings] = fields[..] else {
+        return Err("Failed to parse header. Is your search index valid?".into());
+    };
+    let (total, total_length, postings) = (total.parse::<f64>()?, total_length.parse::<f64>()?, postings.parse::<u64>()?);
+    let average = total_length / total;
+
+    // scores from the postings of the query terms, by document
+    let mut scores = HashMap::<u64, f64>::new();
+    for token in query {
+        let lines = sorted::starting_with_from(path, postings, &format!("{}\t", token))?.collect::<Result<Vec<String>>>()?;
+        let idf = ((total - lines.len() as f64 + 0.5) / (lines.len() as f64 + 0.5) + 1.0).ln();
+        for line in lines {
+            let fields = line.split('\t').collect::<Vec<&str>>();
+            let [_, document, length, frequency] = fields[..] else {
+                return Err("Malformed posting. Is your search index valid?".into());
+            };
+            let (document, length, frequency) = (document.parse::<u64>()?, length.parse::<f64>()?, frequency.parse::<f64>()?);
+            let score = idf * frequency * (k1 + 1.0) / (frequency + k1 * (1.0 - b + b * length / average));
+            *scores.entry(document).or_default() += score;
+        }
+    }
+
+    // then the documents that scored, best first, until we have enough that pass the filters
+    let mut scored = scores.into_iter().collect::<Vec<(u64, f64)>>();
+    scored.sort_by(|x, y| y.1.total_cmp(&x.1).then(x.0.cmp(&y.0)));
+    let mut hits = Vec::new();
+    for (document, score) in scored {
+        if hits.len() >= limit {
+            break;
+        }
+        reader.seek(SeekFrom::Start(document))?;
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let fields = line.trim_end_matches('\n').splitn(6, '\t').collect::<Vec<&str>>();
+        let ["D", _, title, language, part, gloss] = fields[..] else {
+            return Err("Posting for missing document. Is your search index valid?".into());
+        };
+        if lang.is_none_or(|lang| lang == language) && pos.is_none_or(|pos| pos.eq_ignore_ascii_case(part)) {
+            hits.push(Hit {
+                title: title.to_string(),
+                lang: language.to_string(),
+                pos: part.to_string(),
+                gloss: gloss.to_string(),
+                score,
+            });
+        }
+    }
+    return Ok(hits);
+}
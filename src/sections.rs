@@ -0,0 +1,52 @@
+use crate::entry;
+use crate::state::State;
+
+// which sections of an entry to show: the config file's include/exclude lists, narrowed by
+// `--section` (by kind: etymology, pos, usage-notes...), `--pos` (by part of speech),
+// and `--etym` (by homograph)
+
+// https://en.wiktionary.org/wiki/Wiktionary:Entry_layout#Part_of_speech
+const parts_of_speech: &[&str] = &[
+    "Adjective", "Adverb", "Affix", "Article", "Circumfix", "Classifier", "Conjunction",
+    "Contraction", "Counter", "Determiner", "Ideophone", "Infix", "Interfix", "Interjection",
+    "Letter", "Noun", "Numeral", "Participle", "Particle", "Phrase", "Postposition", "Prefix",
+    "Preposition", "Prepositional phrase", "Pronoun", "Proper noun", "Proverb", "Punctuation mark",
+    "Suffix", "Symbol", "Verb", "Abbreviation", "Acronym", "Initialism", "Romanization",
+];
+
+pub fn is_part_of_speech(heading: &str) -> bool {
+    return parts_of_speech.iter().any(|pos| pos.eq_ignore_ascii_case(heading));
+}
+
+// "usage-notes" for "Usage notes", and "etymology" for "Etymology 2"
+fn matches(selector: &str, heading: &str) -> bool {
+    if selector.eq_ignore_ascii_case("pos") {
+        return is_part_of_speech(heading);
+    }
+    let selector = selector.replace('-', " ");
+    return heading.get(..selector.len()).is_some_and(|start| start.eq_ignore_ascii_case(&selector));
+}
+
+/// Whether this is a part of speech other than the one asked for with `--pos`
+pub fn is_other_part_of_speech(heading: &str, state: &State) -> bool {
+    return state.pos.as_ref().is_some_and(|pos| is_part_of_speech(heading) && !pos.eq_ignore_ascii_case(heading));
+}
+
+/// Whether this is a numbered etymology other than the one asked for with `--etym`
+pub fn is_other_etymology(heading: &str, state: &State) -> bool {
+    return state.etymology.is_some_and(|wanted| entry::etymology_number(heading).is_some_and(|number| number != wanted));
+}
+
+/// Whether a section with this (non-language) heading should be shown
+pub fn is_visible(heading: &str, state: &State) -> bool {
+    // already shown compactly under the language heading
+    if state.pronunciation && matches("pronunciation", heading) {
+        return false;
+    }
+    if !state.sections.is_empty() {
+        return state.sections.iter().any(|selector| matches(selector, heading));
+    }
+    let included = state.include.iter().any(|section| matches(section, heading));
+    let excluded = state.exclude.iter().any(|section| matches(section, heading));
+    return included || !excluded;
+}
@@ -0,0 +1,204 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fs::{self, File};
+use std::io::*;
+use std::path::{Path, PathBuf};
+
+// the indexes we build are plain text, a record to a line, sorted so that the lines for a key sit together.
+// a lookup binary searches for them by seeking around the file, rather than reading the whole thing.
+// building one means sorting more lines than fit comfortably in memory, so sorted runs of them
+// get spilled to disk as we go and merged at the end
+
+/// How much text to sort in memory before spilling it
+const default_run_size: usize = 64 << 20;
+
+/// Sorts lines, spilling to runs next to `path` when there are too many of them
+pub struct Writer {
+    path: PathBuf,
+    run_size: usize,
+    lines: Vec<String>,
+    size: usize,
+    runs: Vec<PathBuf>,
+}
+
+impl Writer {
+    pub fn new(path: impl AsRef<Path>) -> Writer {
+        return Writer::with_run_size(path, default_run_size);
+    }
+
+    /// A writer that spills every `run_size` bytes of lines
+    pub fn with_run_size(path: impl AsRef<Path>, run_size: usize) -> Writer {
+        return Writer { path: path.as_ref().to_path_buf(), run_size, lines: Vec::new(), size: 0, runs: Vec::new() };
+    }
+
+    pub fn push(&mut self, line: String) -> Result<()> {
+        self.size += line.len();
+        self.lines.push(line);
+        if self.size >= self.run_size {
+            self.spill()?;
+        }
+        return Ok(());
+    }
+
+    fn spill(&mut self) -> Result<()> {
+        self.lines.sort_unstable();
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".run{}", self.runs.len()));
+        let run = PathBuf::from(name);
+        let mut writer = BufWriter::new(File::create(&run)?);
+        for line in self.lines.drain(..) {
+            writeln!(writer, "{}", line)?;
+        }
+        writer.flush()?;
+        self.runs.push(run);
+        self.size = 0;
+        return Ok(());
+    }
+
+    /// Every line pushed, in order, without duplicates
+    pub fn sorted(mut self) -> Result<Merge> {
+        // everything fit in memory, no need to go through the disk
+        if self.runs.is_empty() {
+            self.lines.sort_unstable();
+            let lines = std::mem::take(&mut self.lines).into_iter();
+            return Ok(Merge { lines, runs: Vec::new(), heap: BinaryHeap::new(), paths: Vec::new(), last: None });
+        }
+        if !self.lines.is_empty() {
+            self.spill()?;
+        }
+        let mut merge = Merge { lines: Vec::new().into_iter(), runs: Vec::new(), heap: BinaryHeap::new(), paths: Vec::new(), last: None };
+        for (i, run) in self.runs.iter().enumerate() {
+            let mut reader = BufReader::new(File::open(run)?);
+            if let Some(line) = read(&mut reader)? {
+                merge.heap.push(Reverse((line, i)));
+            }
+            merge.runs.push(reader);
+        }
+        merge.paths = std::mem::take(&mut self.runs);
+        return Ok(merge);
+    }
+
+    /// Writes every line pushed out to the file, in order
+    pub fn finish(self) -> Result<()> {
+        let mut writer = BufWriter::new(File::create(&self.path)?);
+        for line in self.sorted()? {
+            writeln!(writer, "{}", line?)?;
+        }
+        return writer.flush();
+    }
+}
+
+/// The lines of a `Writer`, merged back together from its runs.
+/// The runs are deleted once it's done with
+pub struct Merge {
+    /// The lines, when there weren't enough of them to spill
+    lines: std::vec::IntoIter<String>,
+    runs: Vec<BufReader<File>>,
+    /// The smallest line not yet taken from each run, and which run it's from
+    heap: BinaryHeap<Reverse<(String, usize)>>,
+    paths: Vec<PathBuf>,
+    last: Option<String>,
+}
+
+impl Iterator for Merge {
+    type Item = Result<String>;
+
+    fn next(&mut self) -> Option<Result<String>> {
+        loop {
+            let line = if self.runs.is_empty() {
+                self.lines.next()?
+            } else {
+                let Reverse((line, run)) = self.heap.pop()?;
+                match read(&mut self.runs[run]) {
+                    Ok(Some(next)) => self.heap.push(Reverse((next, run))),
+                    Ok(None) => (),
+                    Err(error) => return Some(Err(error)),
+                }
+                line
+            };
+            if self.last.as_ref() == Some(&line) {
+                continue;
+            }
+            self.last = Some(line.clone());
+            return Some(Ok(line));
+        }
+    }
+}
+
+impl Drop for Merge {
+    fn drop(&mut self) {
+        for path in self.paths.iter() {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+// a line without its newline, or None at the end
+fn read(reader: &mut impl BufRead) -> Result<Option<String>> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    if line.ends_with('\n') {
+        line.pop();
+    }
+    return Ok(Some(line));
+}
+
+/// The lines of a sorted file that start with `prefix`, in order
+pub fn starting_with(path: impl AsRef<Path>, prefix: &str) -> Result<Lines> {
+    return starting_with_from(path, 0, prefix);
+}
+
+/// `starting_with`, for files where the sorted lines only start at byte `start`
+pub fn starting_with_from(path: impl AsRef<Path>, start: u64, prefix: &str) -> Result<Lines> {
+    let file = File::open(path)?;
+    let end = file.metadata()?.len();
+    let mut reader = BufReader::new(file);
+
+    // the first line boundary at or after `low` has the first line that's not less than `prefix`
+    let (mut low, mut high) = (start, end);
+    while low < high {
+        let middle = low + (high - low) / 2;
+        let (_, line) = line_after(&mut reader, start, middle)?;
+        if line.is_some_and(|line| line.as_str() < prefix) {
+            low = middle + 1;
+        } else {
+            high = middle;
+        }
+    }
+    let (boundary, _) = line_after(&mut reader, start, low)?;
+    reader.seek(SeekFrom::Start(boundary))?;
+    return Ok(Lines { reader, prefix: prefix.to_string() });
+}
+
+// the first line starting at or after `position`, and where it starts
+fn line_after(reader: &mut BufReader<File>, start: u64, position: u64) -> Result<(u64, Option<String>)> {
+    let mut boundary = position;
+    if position > start {
+        // the previous byte is either the newline that ends a line, or some way into one
+        reader.seek(SeekFrom::Start(position - 1))?;
+        boundary = position - 1 + reader.skip_until(b'\n')? as u64;
+    } else {
+        reader.seek(SeekFrom::Start(position))?;
+    }
+    return Ok((boundary, read(reader)?));
+}
+
+/// Lines of a sorted file, for as long as they start with a prefix
+pub struct Lines {
+    reader: BufReader<File>,
+    prefix: String,
+}
+
+impl Iterator for Lines {
+    type Item = Result<String>;
+
+    fn next(&mut self) -> Option<Result<String>> {
+        return match read(&mut self.reader) {
+            Ok(Some(line)) if line.starts_with(&self.prefix) => Some(Ok(line)),
+            Ok(_) => None,
+            Err(error) => Some(Err(error)),
+        };
+    }
+}
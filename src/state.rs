@@ -1,6 +1,39 @@
+#[derive(Clone)]
 pub struct State {
     pub full: bool,
     pub lang: String,
+    pub lang_given: bool,
+    pub verbose: bool,
+    pub lemma: bool,
+    pub pronunciation: bool,
+    pub thesaurus: bool,
+    pub translate: Vec<String>,
+    pub follow: bool,
+    pub etymology_tree: bool,
+    pub depth: usize,
+    pub prefix: Option<String>,
+    pub pattern: Option<String>,
+    pub regex: bool,
+    pub query: Option<String>,
+    pub forms: Option<String>,
+    pub pos: Option<String>,
+    pub sections: Vec<String>,
+    pub etymology: Option<usize>,
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    pub length: Option<usize>,
+    pub limit: Option<usize>,
+    /// How wide to wrap terminal output, if at all
+    pub width: Option<usize>,
+    /// Whether to style terminal output. `Auto` is settled by `resolve`, and means no styling until it is
+    pub color: crate::theme::Color,
+    pub theme: crate::theme::Theme,
+    pub format: crate::markup::Format,
+    pub link: String,
+    pub links: bool,
+    /// Whether links can be clickable in the terminal, with OSC 8
+    pub hyperlinks: bool,
+    pub pager: bool,
 }
 
 impl State {
@@ -9,6 +42,53 @@ impl State {
         return State {
             full: false,
             lang: String::from("English"),
+            lang_given: false,
+            verbose: false,
+            lemma: false,
+            pronunciation: false,
+            thesaurus: false,
+            translate: Vec::new(),
+            follow: false,
+            etymology_tree: false,
+            depth: 5,
+            prefix: None,
+            pattern: None,
+            regex: false,
+            query: None,
+            forms: None,
+            pos: None,
+            sections: Vec::new(),
+            etymology: None,
+            include: Vec::new(),
+            exclude: crate::config::default_excluded_sections.iter().map(|section| section.to_string()).collect(),
+            length: None,
+            limit: None,
+            width: None,
+            color: crate::theme::Color::Auto,
+            theme: crate::theme::Theme::default(),
+            format: crate::markup::Format::Terminal,
+            link: String::from(crate::markup::default_link),
+            links: false,
+            hyperlinks: false,
+            pager: true,
+        }
+    }
+}
+
+impl Default for State {
+    fn default() -> State {
+        return State::new();
+    }
+}
+
+impl State {
+    /// Settles whatever depends on where we're running: whether to colour, whether links are clickable,
+    /// and how wide the terminal is. Done once, so that rendering the same entry always gives the same bytes
+    pub fn resolve(&mut self) {
+        self.hyperlinks = crate::theme::hyperlinks(self);
+        self.color = if crate::theme::enabled(self) { crate::theme::Color::Always } else { crate::theme::Color::Never };
+        if self.width.is_none() {
+            self.width = terminal_size::terminal_size().map(|(terminal_size::Width(width), _)| width as usize);
         }
     }
 }
@@ -0,0 +1,194 @@
+use std::io::IsTerminal;
+
+use crate::state::State;
+
+// styles are written the way you'd say them, in the config file:
+//   theme.headword = bold
+//   theme.sense-number = bold blue
+//   theme.example = italic bright-black
+// and anything else is taken as raw SGR parameters, for the 256-colour crowd: `38;5;244`
+
+/// A terminal style, as the SGR parameters that start it. Empty means unstyled.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Style(String);
+
+const attributes: &[(&str, &str)] = &[
+    ("bold", "1"), ("dim", "2"), ("italic", "3"), ("underline", "4"), ("reverse", "7"),
+    ("black", "30"), ("red", "31"), ("green", "32"), ("yellow", "33"),
+    ("blue", "34"), ("magenta", "35"), ("cyan", "36"), ("white", "37"),
+    ("bright-black", "90"), ("bright-red", "91"), ("bright-green", "92"), ("bright-yellow", "93"),
+    ("bright-blue", "94"), ("bright-magenta", "95"), ("bright-cyan", "96"), ("bright-white", "97"),
+];
+
+impl Style {
+    /// Parses a space-separated list of attribute names, colour names and SGR parameters
+    pub fn parse(spec: &str) -> Option<Style> {
+        let mut codes = Vec::new();
+        for word in spec.split_whitespace() {
+            if word == "none" {
+                continue;
+            }
+            match attributes.iter().find(|(name, _)| name.eq_ignore_ascii_case(word)) {
+                Some((_, code)) => codes.push(*code),
+                None if word.split(';').all(|code| code.parse::<u8>().is_ok()) => codes.push(word),
+                None => return None,
+            }
+        }
+        return Some(Style(codes.join(";")));
+    }
+
+    pub const reset: &'static str = "\x1b[0m";
+
+    /// The escape that turns this style on, or nothing
+    pub fn start(&self) -> String {
+        if self.0.is_empty() {
+            return String::new();
+        }
+        return format!("\x1b[{}m", self.0);
+    }
+
+    /// `text` with this style on, and everything off again after it.
+    /// Anything styled inside turns everything off at its end, so this goes back on after each of those
+    pub fn paint(&self, text: &str) -> String {
+        if self.0.is_empty() || text.is_empty() {
+            return text.to_string();
+        }
+        let start = self.start();
+        return format!("{}{}{}", start, text.replace(Style::reset, &format!("{}{}", Style::reset, start)), Style::reset);
+    }
+}
+
+/// The style of each part of an entry
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    /// The word itself, where an entry names it: homograph labels and headword lines
+    pub headword: Style,
+    /// Language and section headings
+    pub heading: Style,
+    /// Part of speech headings
+    pub part_of_speech: Style,
+    pub sense_number: Style,
+    /// Usage labels, like "(aviation)" or "(archaic)"
+    pub label: Style,
+    /// Usage examples and quotations
+    pub example: Style,
+    /// The languages an etymology passes through
+    pub etymology_language: Style,
+    /// Bold and italic text, as marked up in the wikitext
+    pub bold: Style,
+    pub italic: Style,
+    /// Links to other entries
+    pub link: Style,
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        let style = |spec| Style::parse(spec).unwrap_or_default();
+        return Theme {
+            headword: style("bold"),
+            heading: style("bold"),
+            part_of_speech: style("bold"),
+            sense_number: style("bold"),
+            label: style("italic"),
+            example: style("dim"),
+            etymology_language: style("italic"),
+            bold: style("bold"),
+            italic: style("italic"),
+            link: style("underline"),
+        };
+    }
+}
+
+impl Theme {
+    /// No styling at all
+    pub fn plain() -> Theme {
+        return Theme {
+            headword: Style::default(),
+            heading: Style::default(),
+            part_of_speech: Style::default(),
+            sense_number: Style::default(),
+            label: Style::default(),
+            example: Style::default(),
+            etymology_language: Style::default(),
+            bold: Style::default(),
+            italic: Style::default(),
+            link: Style::default(),
+        };
+    }
+
+    /// Sets the style of one part by its name in the config file, if both are valid
+    pub fn set(&mut self, key: &str, spec: &str) -> Result<(), String> {
+        let style = Style::parse(spec).ok_or_else(|| format!("\"{}\" is not a style", spec))?;
+        match key {
+            "headword" => self.headword = style,
+            "heading" => self.heading = style,
+            "part-of-speech" | "pos" => self.part_of_speech = style,
+            "sense-number" => self.sense_number = style,
+            "label" => self.label = style,
+            "example" => self.example = style,
+            "etymology-language" => self.etymology_language = style,
+            "bold" => self.bold = style,
+            "italic" => self.italic = style,
+            "link" => self.link = style,
+            _ => return Err(format!("there is no \"{}\" to style", key)),
+        }
+        return Ok(());
+    }
+}
+
+/// When to style output, from `--color` or the `color` config option
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Color {
+    Always,
+    Never,
+    /// Only when writing to a terminal, and NO_COLOR isn't set
+    Auto,
+}
+
+impl Color {
+    pub fn parse(value: &str) -> Option<Color> {
+        return match value {
+            "always" => Some(Color::Always),
+            "never" => Some(Color::Never),
+            "auto" => Some(Color::Auto),
+            _ => None,
+        };
+    }
+}
+
+/// Whether to style output at all, looking at where it's going for `Auto`
+pub fn enabled(state: &State) -> bool {
+    // https://no-color.org: set and not empty means no colour
+    return match state.color {
+        Color::Always => true,
+        Color::Never => false,
+        Color::Auto => std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty()) && std::io::stdout().is_terminal(),
+    };
+}
+
+/// The theme to draw with: the configured one, or none at all if colour is off (or not yet settled)
+pub fn active(state: &State) -> Theme {
+    if state.color == Color::Always {
+        return state.theme.clone();
+    }
+    return Theme::plain();
+}
+
+/// Whether the terminal will make links clickable, rather than printing the escapes as junk.
+/// Never without colour, since plain output has no escapes at all. Otherwise there's no asking the terminal,
+/// so we go by what it says it is, unless FORCE_HYPERLINK settles it
+pub fn hyperlinks(state: &State) -> bool {
+    if !enabled(state) {
+        return false;
+    }
+    if let Some(force) = std::env::var_os("FORCE_HYPERLINK") {
+        return force != "0";
+    }
+    let var = |name: &str| std::env::var(name).unwrap_or_default();
+    // https://github.com/Alhadis/OSC8-Adoption
+    return matches!(var("TERM_PROGRAM").as_str(), "iTerm.app" | "WezTerm" | "vscode" | "ghostty" | "Hyper" | "Tabby")
+        || var("VTE_VERSION").parse::<u32>().is_ok_and(|version| version >= 5000)
+        || ["kitty", "alacritty", "foot", "ghostty", "wezterm", "contour"].iter().any(|terminal| var("TERM").contains(terminal))
+        || !var("WT_SESSION").is_empty()
+        || !var("KONSOLE_VERSION").is_empty();
+}
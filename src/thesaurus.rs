@@ -0,0 +1,225 @@
+use parse_wiki_text::*;
+
+use crate::entry;
+
+// inspired by thesauromatic: words that mean the same, the opposite, and so on, grouped by sense
+// these come from three places: the Synonyms/Antonyms/etc sections of an entry,
+// {{syn}} and {{ant}} lines under individual senses, and the Thesaurus: namespace
+
+/// The kinds of relation we collect, and the section headings they come from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Relation {
+    Synonym,
+    Antonym,
+    Hyponym,
+    Derived,
+    Related,
+}
+
+const relation_headings: &[(&str, Relation)] = &[
+    ("Synonyms", Relation::Synonym),
+    ("Antonyms", Relation::Antonym),
+    ("Hyponyms", Relation::Hyponym),
+    ("Derived terms", Relation::Derived),
+    ("Related terms", Relation::Related),
+];
+
+impl std::fmt::Display for Relation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let heading = relation_headings.iter().find(|(_, relation)| relation == self).unwrap().0;
+        return write!(f, "{}", heading);
+    }
+}
+
+/// The related words for one sense. A gloss of "" means the section didn't say which sense.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Group {
+    pub gloss: String,
+    pub words: Vec<(Relation, Vec<String>)>,
+}
+
+impl Group {
+    pub fn get(&self, relation: Relation) -> &[String] {
+        return self.words.iter().find(|(kind, _)| *kind == relation).map_or(&[], |(_, words)| words);
+    }
+
+    fn add(&mut self, relation: Relation, word: String) {
+        if let Some((_, words)) = self.words.iter_mut().find(|(kind, _)| *kind == relation) {
+            if !words.contains(&word) {
+                words.push(word);
+            }
+        } else {
+            self.words.push((relation, vec![word]));
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Thesaurus {
+    pub groups: Vec<Group>,
+    /// Thesaurus: pages mentioned along the way, for the caller to look up and `merge`
+    pub references: Vec<String>,
+}
+
+impl Thesaurus {
+    /// Collects the related words for `lang` from an entry page
+    pub fn parse(definition: &Output, lang: &str) -> Thesaurus {
+        let mut thesaurus = Thesaurus::default();
+        for sense in entry::senses(definition) {
+            if sense.lang != lang {
+                continue;
+            }
+            // usually on a `#:` line of its own, but sometimes tacked onto the gloss
+            let gloss = sense.gloss();
+            let details = sense.details.iter().flat_map(|details| details.iter());
+            for node in sense.nodes.iter().copied().chain(details) {
+                thesaurus.add_inline(&gloss, node);
+            }
+        }
+        for (heading, relation) in relation_headings {
+            for section in entry::sections(definition, lang, heading) {
+                for node in section {
+                    thesaurus.add_list(*relation, node);
+                }
+            }
+        }
+        return thesaurus;
+    }
+
+    /// Adds the related words from a `Thesaurus:` page
+    pub fn merge(&mut self, definition: &Output, lang: &str) {
+        // thesaurus pages go language > part of speech > "Sense: gloss" > relation
+        let mut in_lang = false;
+        let mut gloss = String::new();
+        let mut relation = None;
+        for node in definition.nodes.iter() {
+            match node {
+                Node::Heading { nodes, level, .. } => {
+                    let value = entry::first_text(nodes).unwrap_or_default();
+                    if *level == 2 {
+                        in_lang = value == lang;
+                    } else if let Some(sense) = value.strip_prefix("Sense:") {
+                        gloss = sense.trim().to_string();
+                    }
+                    relation = relation_headings.iter().find(|(heading, _)| *heading == value).map(|(_, relation)| *relation);
+                },
+                Node::UnorderedList { items, .. } if in_lang && let Some(relation) = relation => {
+                    for item in items {
+                        for word in words(&item.nodes, &mut self.references) {
+                            self.group(&gloss).add(relation, word);
+                        }
+                    }
+                },
+                _ => (),
+            }
+        }
+    }
+
+    fn group(&mut self, gloss: &str) -> &mut Group {
+        if let Some(index) = self.groups.iter().position(|group| group.gloss == gloss) {
+            return &mut self.groups[index];
+        }
+        self.groups.push(Group { gloss: gloss.to_string(), ..Default::default() });
+        return self.groups.last_mut().unwrap();
+    }
+
+    // {{syn|en|word|word|Thesaurus:word}} under a sense
+    fn add_inline(&mut self, gloss: &str, node: &Node) {
+        if let Node::Template { name, parameters, .. } = node {
+            let relation = match entry::first_text(name).unwrap_or_default() {
+                "syn" | "synonyms" => Relation::Synonym,
+                "ant" | "antonyms" => Relation::Antonym,
+                "hyp" | "hyponyms" => Relation::Hyponym,
+                _ => return,
+            };
+            for word in entry::positional(parameters).into_iter().skip(1) {
+                if let Some(page) = word.strip_prefix("Thesaurus:") {
+                    self.reference(page);
+                } else if !word.is_empty() {
+                    self.group(gloss).add(relation, word);
+                }
+            }
+        }
+    }
+
+    // * {{sense|gloss}} {{l|en|word}}, {{l|en|word}}
+    fn add_list(&mut self, relation: Relation, node: &Node) {
+        if let Node::UnorderedList { items, .. } = node {
+            for item in items {
+                let gloss = item.nodes.iter().find_map(|node| match node {
+                    Node::Template { name, parameters, .. }
+                    if matches!(entry::first_text(name), Some("sense" | "s")) => entry::positional(parameters).into_iter().next(),
+                    _ => None,
+                }).unwrap_or_default();
+                for word in words(&item.nodes, &mut self.references) {
+                    self.group(&gloss).add(relation, word);
+                }
+            }
+        }
+    }
+
+    fn reference(&mut self, page: &str) {
+        if !self.references.iter().any(|reference| reference == page) {
+            self.references.push(page.to_string());
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.groups.iter().all(|group| group.words.is_empty());
+    }
+}
+
+// the words linked from a line, noting any thesaurus pages it points to
+fn words(nodes: &[Node], references: &mut Vec<String>) -> Vec<String> {
+    let mut words = Vec::new();
+    for node in nodes {
+        match node {
+            Node::Link { target, .. } => {
+                if let Some(page) = target.strip_prefix("Thesaurus:") {
+                    if !references.iter().any(|reference| reference == page) {
+                        references.push(page.to_string());
+                    }
+                } else {
+                    words.push(target.to_string());
+                }
+            },
+            Node::Template { name, parameters, .. } => {
+                let positional = entry::positional(parameters);
+                match entry::first_text(name).unwrap_or_default() {
+                    "l" | "l-lite" | "ll" | "L" => words.extend(positional.into_iter().nth(1)),
+                    // {{ws|word}} on thesaurus pages; newer ones take a language first
+                    "ws" => words.extend(positional.into_iter().take(2).next_back()),
+                    x if x.starts_with("col") || x.starts_with("der") || x.starts_with("rel") || x.starts_with("hyp") =>
+                        words.extend(positional.into_iter().skip(1)),
+                    "seeSynonyms" => {
+                        let page = positional.into_iter().last().unwrap_or_default();
+                        let page = page.strip_prefix("Thesaurus:").unwrap_or(&page);
+                        if !page.is_empty() && !references.iter().any(|reference| reference == page) {
+                            references.push(page.to_string());
+                        }
+                    },
+                    _ => (),
+                }
+            },
+            _ => (),
+        }
+    }
+    words.retain(|word| !word.is_empty());
+    return words;
+}
+
+impl std::fmt::Display for Thesaurus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for group in self.groups.iter().filter(|group| !group.words.is_empty()) {
+            if group.gloss.is_empty() {
+                writeln!(f, "(general)")?;
+            } else {
+                writeln!(f, "{}", group.gloss)?;
+            }
+            for (relation, words) in group.words.iter() {
+                writeln!(f, "  {}: {}", relation, words.join(", "))?;
+            }
+        }
+        return Ok(());
+    }
+}
@@ -0,0 +1,190 @@
+use std::{io::*, fs::File};
+use bzip2::bufread::*;
+use regex::Regex;
+
+use crate::lookup;
+use crate::page::Page;
+use crate::sorted;
+
+type Error = Box<dyn std::error::Error>;
+
+/// A line of the multistream index: where to find a page, and what it's called
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub offset: u64,
+    pub id: u64,
+    pub title: String,
+}
+
+/// Streams every entry of the index, in dump order (which is _not_ alphabetical)
+pub fn entries(file: File) -> impl Iterator<Item = std::result::Result<Entry, Error>> {
+    let reader = BufReader::new(MultiBzDecoder::new(BufReader::new(file)));
+    return reader.lines().map(|line| {
+        let line = line?;
+
+        // format: file-offset:page-id:page-title
+        let mut fields = line.splitn(3, ':');
+        let (Some(offset), Some(id), Some(title)) = (fields.next(), fields.next(), fields.next()) else {
+            return Err(format!("Failed to parse line {:?}. Is your index file valid?", line).into());
+        };
+
+        return Ok(Entry {
+            offset: offset.parse::<u64>()?,
+            id: id.parse::<u64>()?,
+            // titles in the index are xml-escaped, same as in the dump itself
+            title: quick_xml::escape::unescape(title)?.into_owned(),
+        });
+    });
+}
+
+/// Restrictions on which titles a search turns up, beyond the title itself
+#[derive(Debug, Clone, Default)]
+pub struct Constraints<'a> {
+    /// Only titles with a section for this language
+    pub lang: Option<&'a str>,
+    /// Only titles with this part of speech (in `lang`, if given)
+    pub pos: Option<&'a str>,
+    /// Only titles exactly this many characters long
+    pub length: Option<usize>,
+}
+
+impl Constraints<'_> {
+    // the cheap checks, before we go and decompress anything
+    fn title_ok(&self, title: &str) -> bool {
+        return self.length.is_none_or(|length| title.chars().count() == length);
+    }
+
+    fn needs_page(&self) -> bool {
+        return self.lang.is_some() || self.pos.is_some();
+    }
+
+    fn page_ok(&self, page: &Page) -> bool {
+        if let Some(lang) = self.lang && !page.has_language(lang) {
+            return false;
+        }
+        if let Some(pos) = self.pos && !page.has_part_of_speech(self.lang, pos) {
+            return false;
+        }
+        return true;
+    }
+}
+
+/// Every title for which `matches` holds and which meets the `constraints`.
+/// Calls `found` with each title as it's found, and stops after `limit` of them.
+pub fn search(matches: impl Fn(&str) -> bool, constraints: &Constraints, limit: Option<usize>,
+    found: impl FnMut(&str)) -> std::result::Result<(), Error> {
+    let file = File::open(crate::index_path)?;
+    return search_in(entries(file), matches, constraints, limit, found);
+}
+
+fn search_in(entries: impl Iterator<Item = std::result::Result<Entry, Error>>, matches: impl Fn(&str) -> bool,
+    constraints: &Constraints, limit: Option<usize>, mut found: impl FnMut(&str)) -> std::result::Result<(), Error> {
+    let mut block = Block::new();
+    let mut count = 0;
+    for entry in entries {
+        if limit.is_some_and(|limit| count >= limit) {
+            break;
+        }
+        let entry = entry?;
+        if !matches(&entry.title) || !constraints.title_ok(&entry.title) {
+            continue;
+        }
+        if constraints.needs_page() && !block.get(&entry)?.is_some_and(|page| constraints.page_ok(page)) {
+            continue;
+        }
+        found(&entry.title);
+        count += 1;
+    }
+    return Ok(());
+}
+
+/// Every title starting with `prefix`, optionally only those with a section for `lang`.
+/// In order, if `--build-index` has sorted them
+pub fn prefix(prefix: &str, lang: Option<&str>, limit: Option<usize>, found: impl FnMut(&str))
+    -> std::result::Result<(), Error> {
+    let constraints = Constraints { lang, ..Default::default() };
+    return match sorted_entries(crate::titles_index_path, prefix) {
+        Ok(entries) => search_in(entries, |_| true, &constraints, limit, found),
+        // the slow way: every title, in dump order
+        Err(_) => search(|title| title.starts_with(prefix), &constraints, limit, found),
+    };
+}
+
+// the sorted title index, one line per title:
+//   title \t offset \t id
+// in order, so the titles with a given prefix are all together and can be found without reading the rest
+
+/// Writes out the titles of the multistream index in order, for prefix searches
+pub fn build(index: File, path: &str) -> std::result::Result<(), Error> {
+    let mut titles = sorted::Writer::new(path);
+    for entry in entries(index) {
+        let entry = entry?;
+        // titles can't have tabs or newlines in them, so there's nothing to clean
+        titles.push(format!("{}\t{}\t{}", entry.title, entry.offset, entry.id))?;
+    }
+    titles.finish()?;
+    return Ok(());
+}
+
+/// The entries of the sorted title index whose titles start with `prefix`, in order
+fn sorted_entries(path: &str, prefix: &str) -> std::result::Result<impl Iterator<Item = std::result::Result<Entry, Error>>, Error> {
+    let lines = sorted::starting_with(path, prefix)?;
+    return Ok(lines.map(|line| {
+        let line = line?;
+        let mut fields = line.rsplitn(3, '\t');
+        let (Some(id), Some(offset), Some(title)) = (fields.next(), fields.next(), fields.next()) else {
+            return Err("Failed to parse line. Is your title index valid?".into());
+        };
+        return Ok(Entry { offset: offset.parse()?, id: id.parse()?, title: title.to_string() });
+    }));
+}
+
+/// Turns a crossword-style glob (`c?t`, `*ology`) into an anchored regex
+pub fn glob_to_regex(glob: &str) -> std::result::Result<Regex, regex::Error> {
+    let mut pattern = String::from("^");
+    for c in glob.chars() {
+        match c {
+            '?' => pattern.push('.'),
+            '*' => pattern.push_str(".*"),
+            c => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    pattern.push('$');
+    return Regex::new(&pattern);
+}
+
+/// Headwords to offer when tab-completing `partial`, for the REPL and shell completion alike:
+/// the first `limit` of them, alphabetically
+pub fn complete(partial: &str, limit: usize) -> Vec<String> {
+    let mut titles = Vec::new();
+    // sorted, the first few found are the first few. otherwise they're in dump order, and all of them have to be sorted
+    let sorted = std::path::Path::new(crate::titles_index_path).exists();
+    // a missing index just means no completions
+    let _ = prefix(partial, None, if sorted { Some(limit) } else { None }, |title| titles.push(title.to_string()));
+    titles.sort();
+    titles.truncate(limit);
+    return titles;
+}
+
+/// The most recently decompressed chunk of the dump.
+/// Entries are in dump order, so matches from the same chunk come one after another
+/// and we only need to hold onto the last one.
+#[derive(Default)]
+pub struct Block {
+    offset: Option<u64>,
+    pages: Vec<Page>,
+}
+
+impl Block {
+    pub fn new() -> Block {
+        return Block { offset: None, pages: Vec::new() };
+    }
+
+    pub fn get(&mut self, entry: &Entry) -> std::result::Result<Option<&Page>, Error> {
+        if self.offset != Some(entry.offset) {
+            self.pages = lookup::read_block(entry.offset)?;
+            self.offset = Some(entry.offset);
+        }
+        return Ok(self.pages.iter().find(|page| page.id == entry.id));
+    }
+}
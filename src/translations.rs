@@ -0,0 +1,120 @@
+use parse_wiki_text::*;
+
+use crate::entry;
+
+// https://en.wiktionary.org/wiki/Wiktionary:Translations
+// a translations section is a run of tables, each wrapped in {{trans-top|gloss}} ... {{trans-bottom}},
+// with one bulleted line per language: `* French: {{t+|fr|banque|f}}`
+// (and sub-bullets for varieties: `*: Mandarin: {{t|cmn|...}}`)
+
+/// One translation of a word into some language
+#[derive(Debug, Clone, PartialEq)]
+pub struct Translation {
+    /// The language name, as written at the start of the line
+    pub lang: String,
+    /// The language code, as passed to the template
+    pub code: String,
+    /// The page the translation is on
+    pub term: String,
+    /// How to write it, if not the same as the page, e.g. with stress marks
+    pub alt: Option<String>,
+    pub genders: Vec<String>,
+    pub transliteration: Option<String>,
+}
+
+/// The translations for one sense of the word
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Table {
+    pub gloss: String,
+    pub translations: Vec<Translation>,
+}
+
+/// Every translation table under `lang`
+pub fn parse(definition: &Output, lang: &str) -> Vec<Table> {
+    let mut tables = Vec::new();
+    for section in entry::sections(definition, lang, "Translations") {
+        let mut table: Option<Table> = None;
+        for node in section {
+            match node {
+                Node::Template { name, parameters, .. } => match entry::first_text(name).unwrap_or_default() {
+                    "trans-top" | "checktrans-top" | "trans-top-also" => {
+                        tables.extend(table.take());
+                        let gloss = entry::positional(parameters).into_iter().next().unwrap_or_default();
+                        table = Some(Table { gloss, ..Default::default() });
+                    },
+                    "trans-bottom" | "checktrans-bottom" => tables.extend(table.take()),
+                    _ => (),
+                },
+                Node::UnorderedList { items, .. } => {
+                    let table = table.get_or_insert_with(Table::default);
+                    collect(items, &mut table.translations);
+                },
+                _ => (),
+            }
+        }
+        tables.extend(table);
+    }
+    tables.retain(|table| !table.translations.is_empty());
+    return tables;
+}
+
+fn collect(items: &[ListItem], translations: &mut Vec<Translation>) {
+    for item in items {
+        collect_line(&item.nodes, translations);
+    }
+}
+
+fn collect_line(nodes: &[Node], translations: &mut Vec<Translation>) {
+    // `French: ...`, the name being everything before the colon
+    let lang = match nodes.first() {
+        Some(Node::Text { value, .. }) => value.split(':').next().unwrap_or_default().trim().to_string(),
+        _ => String::new(),
+    };
+    for node in nodes {
+        match node {
+            Node::Template { name, parameters, .. }
+            if matches!(entry::first_text(name), Some("t" | "t+" | "tt" | "tt+" | "t-check" | "t+check")) => {
+                let positional = entry::positional(parameters);
+                let (Some(code), Some(term)) = (positional.first(), positional.get(1)) else {
+                    continue;
+                };
+                translations.push(Translation {
+                    lang: lang.clone(),
+                    code: code.clone(),
+                    term: term.clone(),
+                    alt: entry::named(parameters, "alt").filter(|alt| !alt.is_empty()),
+                    genders: positional.iter().skip(2).filter(|gender| !gender.is_empty()).cloned().collect(),
+                    transliteration: entry::named(parameters, "tr"),
+                });
+            },
+            // varieties, under their macrolanguage
+            Node::UnorderedList { items, .. } => collect(items, translations),
+            Node::DefinitionList { items, .. } => {
+                for item in items {
+                    collect_line(&item.nodes, translations);
+                }
+            },
+            _ => (),
+        }
+    }
+}
+
+impl Translation {
+    /// Whether this is in one of the `wanted` languages, by name or by code
+    pub fn is_in(&self, wanted: &[String]) -> bool {
+        return wanted.iter().any(|wanted| wanted.eq_ignore_ascii_case(&self.lang) || *wanted == self.code);
+    }
+}
+
+impl std::fmt::Display for Translation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.alt.as_ref().unwrap_or(&self.term))?;
+        if !self.genders.is_empty() {
+            write!(f, " ({})", self.genders.join(", "))?;
+        }
+        if let Some(transliteration) = &self.transliteration {
+            write!(f, " [{}]", transliteration)?;
+        }
+        return Ok(());
+    }
+}
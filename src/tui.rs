@@ -0,0 +1,461 @@
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, Clear, List, ListState, Paragraph, Tabs};
+use ratatui::{DefaultTerminal, Frame};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use crate::state::State;
+use crate::{display, entry, lookup, page::Page, theme, titles};
+
+// `dictionarium tui`: one entry at a time, in a scrollable pane,
+// with the entry's languages along the top and a search box along the bottom.
+// the entry is laid out by display::render like anywhere else, and its escapes turned back into styles here
+
+const help: &str = "/ search · tab link · enter open · ←/→ back/forward · [/] language · q quit";
+
+/// How long typing has to pause before we go looking for completions
+const debounce: Duration = Duration::from_millis(150);
+
+/// Runs the browser until it's quit, starting at `word` if given
+pub fn run(word: Option<String>, state: &State) -> std::io::Result<()> {
+    let mut browser = Browser::new(state);
+    match word {
+        Some(word) => browser.open(&word),
+        None => browser.search = Some(Search::default()),
+    }
+    // no terminal to take over (piped, say) is an error like any other.
+    // raw mode may have gone on before it failed, so off it goes again, but nothing gets written out
+    let mut terminal = ratatui::try_init().inspect_err(|_| {
+        let _ = ratatui::crossterm::terminal::disable_raw_mode();
+    })?;
+    let result = browser.run(&mut terminal);
+    ratatui::restore();
+    return result;
+}
+
+#[derive(Default)]
+struct Search {
+    query: String,
+    completions: Vec<String>,
+    selected: ListState,
+    /// When the query last changed, if we've not gone looking for it yet
+    changed: Option<Instant>,
+    /// The query we're looking for completions of, if any
+    looking: Option<String>,
+}
+
+impl Search {
+    fn waiting(&self) -> bool {
+        return self.changed.is_some() || self.looking.is_some();
+    }
+}
+
+/// Finds completions on a thread of its own, so typing never has to wait for the index
+struct Completer {
+    queries: mpsc::Sender<String>,
+    results: mpsc::Receiver<(String, Vec<String>)>,
+}
+
+impl Completer {
+    fn new() -> Completer {
+        let (queries, incoming) = mpsc::channel::<String>();
+        let (outgoing, results) = mpsc::channel();
+        std::thread::spawn(move || {
+            while let Ok(mut query) = incoming.recv() {
+                // if more came in while we were busy, only the latest matters
+                while let Ok(newer) = incoming.try_recv() {
+                    query = newer;
+                }
+                let completions = complete(&query);
+                if outgoing.send((query, completions)).is_err() {
+                    return;
+                }
+            }
+        });
+        return Completer { queries, results };
+    }
+}
+
+struct Browser {
+    state: State,
+    page: Option<Page>,
+    /// The level-2 headings of the page, and which we're showing
+    languages: Vec<String>,
+    language: usize,
+    text: Text<'static>,
+    links: Vec<String>,
+    link: Option<usize>,
+    scroll: u16,
+    /// The width the page was laid out for, so we know when to lay it out again
+    width: u16,
+    /// Titles visited, and where we are among them
+    history: Vec<String>,
+    position: usize,
+    search: Option<Search>,
+    completer: Completer,
+    message: String,
+    quit: bool,
+}
+
+impl Browser {
+    fn new(state: &State) -> Browser {
+        let mut state = state.clone();
+        // we want the styles, and the links numbered so there's something to pick between
+        state.color = theme::Color::Always;
+        state.links = true;
+        // nothing to click on in here
+        state.hyperlinks = false;
+        return Browser {
+            state,
+            page: None,
+            languages: Vec::new(),
+            language: 0,
+            text: Text::default(),
+            links: Vec::new(),
+            link: None,
+            scroll: 0,
+            width: 0,
+            history: Vec::new(),
+            position: 0,
+            search: None,
+            completer: Completer::new(),
+            message: String::new(),
+            quit: false,
+        };
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> std::io::Result<()> {
+        while !self.quit {
+            self.look_for_completions();
+            terminal.draw(|frame| self.draw(frame))?;
+            // while completions are on their way, wake up every so often to check on them
+            if self.search.as_ref().is_some_and(|search| search.waiting()) && !event::poll(Duration::from_millis(30))? {
+                continue;
+            }
+            if let Event::Key(key) = event::read()? && key.kind == KeyEventKind::Press {
+                if self.search.is_some() {
+                    self.search_key(key.code);
+                } else {
+                    self.key(key.code, key.modifiers);
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    /// Opens a page as a new step in the history
+    fn open(&mut self, target: &str) {
+        // `bank#Etymology_2` is still just bank, as far as looking it up goes
+        let title = target.split('#').next().unwrap_or(target);
+        if self.load(title) {
+            self.history.truncate(self.position + 1);
+            self.history.push(title.to_string());
+            self.position = self.history.len() - 1;
+        }
+    }
+
+    fn load(&mut self, title: &str) -> bool {
+        match lookup::lookup(title) {
+            Ok(Some(page)) => {
+                let definition = parse_wiki_text::Configuration::default().parse(&page.text);
+                self.languages = definition.nodes.iter().filter_map(|node| match node {
+                    parse_wiki_text::Node::Heading { nodes, level: 2, .. } => entry::first_text(nodes).map(|lang| lang.trim().to_string()),
+                    _ => None,
+                }).collect();
+                self.language = self.languages.iter().position(|lang| *lang == self.state.lang).unwrap_or_default();
+                self.page = Some(page);
+                self.message.clear();
+                self.lay_out();
+                return true;
+            },
+            Ok(None) => self.message = format!("Could not find word {}.", title),
+            Err(error) => self.message = format!("Could not look up {}: {}", title, error),
+        }
+        return false;
+    }
+
+    fn lay_out(&mut self) {
+        let Some(page) = &self.page else {
+            return;
+        };
+        if let Some(lang) = self.languages.get(self.language) {
+            self.state.lang = lang.clone();
+        }
+        self.state.width = Some(self.width.max(20).into());
+        let rendering = display::render(&page.title, &page.text, &self.state);
+        self.text = styled(&rendering.text);
+        self.links = rendering.links;
+        self.link = None;
+        self.scroll = 0;
+    }
+
+    fn key(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        let lines = self.text.lines.len() as u16;
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => self.quit = true,
+            KeyCode::Char('/') => self.search = Some(Search::default()),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll = (self.scroll + 1).min(lines.saturating_sub(1)),
+            KeyCode::Up | KeyCode::Char('k') => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::PageDown | KeyCode::Char(' ') => self.scroll = (self.scroll + 20).min(lines.saturating_sub(1)),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(20),
+            KeyCode::Home | KeyCode::Char('g') => self.scroll = 0,
+            KeyCode::End | KeyCode::Char('G') => self.scroll = lines.saturating_sub(1),
+            KeyCode::Tab => self.select(1),
+            KeyCode::BackTab => self.select(-1),
+            KeyCode::Enter => {
+                if let Some(target) = self.link.and_then(|link| self.links.get(link)).cloned() {
+                    self.open(&target);
+                }
+            },
+            KeyCode::Left | KeyCode::Backspace | KeyCode::Char('h') if self.position > 0 => {
+                self.position -= 1;
+                let title = self.history[self.position].clone();
+                self.load(&title);
+            },
+            KeyCode::Right | KeyCode::Char('l') if self.position + 1 < self.history.len() => {
+                self.position += 1;
+                let title = self.history[self.position].clone();
+                self.load(&title);
+            },
+            KeyCode::Char(']') if !self.languages.is_empty() => {
+                self.language = (self.language + 1) % self.languages.len();
+                self.lay_out();
+            },
+            KeyCode::Char('[') if !self.languages.is_empty() => {
+                self.language = (self.language + self.languages.len() - 1) % self.languages.len();
+                self.lay_out();
+            },
+            _ => (),
+        }
+    }
+
+    // moves the link selection along by `step`, and scrolls to wherever it's mentioned
+    fn select(&mut self, step: isize) {
+        if self.links.is_empty() {
+            return;
+        }
+        let count = self.links.len() as isize;
+        let link = match self.link {
+            Some(link) => (link as isize + step).rem_euclid(count),
+            None if step < 0 => count - 1,
+            None => 0,
+        } as usize;
+        self.link = Some(link);
+        let marker = format!("[{}]", link + 1);
+        if let Some(line) = self.text.lines.iter().position(|line| line.spans.iter().any(|span| span.content.contains(&marker))) {
+            self.scroll = line.saturating_sub(3) as u16;
+        }
+    }
+
+    fn search_key(&mut self, code: KeyCode) {
+        let Some(search) = &mut self.search else {
+            return;
+        };
+        match code {
+            KeyCode::Esc => {
+                self.search = None;
+                return;
+            },
+            KeyCode::Enter => {
+                let word = search.selected.selected().and_then(|selected| search.completions.get(selected)).cloned()
+                    .unwrap_or_else(|| search.query.trim().to_string());
+                self.search = None;
+                if !word.is_empty() {
+                    self.open(&word);
+                }
+                return;
+            },
+            KeyCode::Down | KeyCode::Tab => search.selected.select_next(),
+            KeyCode::Up | KeyCode::BackTab => search.selected.select_previous(),
+            KeyCode::Backspace => {
+                search.query.pop();
+                search.changed = Some(Instant::now());
+            },
+            KeyCode::Char(c) => {
+                search.query.push(c);
+                search.changed = Some(Instant::now());
+            },
+            _ => (),
+        }
+    }
+
+    /// Asks for completions once typing has paused, and takes them when they come back
+    fn look_for_completions(&mut self) {
+        let Some(search) = &mut self.search else {
+            return;
+        };
+        while let Ok((query, completions)) = self.completer.results.try_recv() {
+            if search.looking.as_ref() == Some(&query) {
+                search.looking = None;
+            }
+            // anything else is for a query since typed over
+            if query == search.query {
+                search.completions = completions;
+                search.selected.select(None);
+            }
+        }
+        if search.changed.is_some_and(|changed| changed.elapsed() >= debounce) {
+            search.changed = None;
+            search.looking = Some(search.query.clone());
+            let _ = self.completer.queries.send(search.query.clone());
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [tabs, body, status, input] = Layout::vertical([
+            Constraint::Length(1), Constraint::Min(3), Constraint::Length(1), Constraint::Length(1),
+        ]).areas(frame.area());
+
+        // a resize means laying out again, at the new width
+        let width = body.width.saturating_sub(2);
+        if width != self.width {
+            self.width = width;
+            let scroll = self.scroll;
+            self.lay_out();
+            self.scroll = scroll;
+        }
+
+        let languages = self.languages.iter().map(|lang| lang.as_str()).collect::<Vec<&str>>();
+        frame.render_widget(Tabs::new(languages).select(self.language)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED)), tabs);
+
+        let title = self.page.as_ref().map_or(String::from("dictionarium"), |page| format!(" {} ", page.title));
+        let text = match self.link {
+            Some(link) => highlighted(&self.text, &format!("[{}]", link + 1)),
+            None => self.text.clone(),
+        };
+        frame.render_widget(Paragraph::new(text).block(Block::bordered().title(title)).scroll((self.scroll, 0)), body);
+
+        let status_line = match self.link.and_then(|link| self.links.get(link)) {
+            Some(target) => format!("[{}] → {}", self.link.unwrap_or_default() + 1, target),
+            None => self.message.clone(),
+        };
+        frame.render_widget(Paragraph::new(status_line), status);
+
+        match &mut self.search {
+            Some(search) => {
+                frame.render_widget(Paragraph::new(format!("/{}", search.query)), input);
+                frame.set_cursor_position((input.x + 1 + search.query.chars().count() as u16, input.y));
+                if !search.completions.is_empty() {
+                    let height = (search.completions.len() as u16 + 2).min(body.height);
+                    let area = Rect { x: body.x, y: body.bottom() - height, width: body.width.min(40), height };
+                    let list = List::new(search.completions.iter().map(|title| title.as_str()))
+                        .block(Block::bordered()).highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+                    frame.render_widget(Clear, area);
+                    frame.render_stateful_widget(list, area, &mut search.selected);
+                }
+            },
+            None => frame.render_widget(Paragraph::new(help).style(Style::default().add_modifier(Modifier::DIM)), input),
+        }
+    }
+}
+
+fn complete(query: &str) -> Vec<String> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+    return titles::complete(query, 20);
+}
+
+// the text with a link's number picked out, so you can see which is selected
+fn highlighted(text: &Text<'static>, marker: &str) -> Text<'static> {
+    let mut text = text.clone();
+    for line in text.lines.iter_mut() {
+        let Some(index) = line.spans.iter().position(|span| span.content.contains(marker)) else {
+            continue;
+        };
+        let span = line.spans.remove(index);
+        let (before, after) = span.content.split_once(marker).unwrap_or_default();
+        let parts = [
+            Span::styled(before.to_string(), span.style),
+            Span::styled(marker.to_string(), span.style.add_modifier(Modifier::REVERSED)),
+            Span::styled(after.to_string(), span.style),
+        ];
+        line.spans.splice(index..index, parts);
+        break;
+    }
+    return text;
+}
+
+/// Turns text styled with escapes (as display::render writes it) into ratatui's styled text
+fn styled(text: &str) -> Text<'static> {
+    let mut lines = Vec::new();
+    let mut style = Style::default();
+    for line in text.lines() {
+        let mut spans = Vec::new();
+        let mut current = String::new();
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '\x1b' {
+                current.push(c);
+                continue;
+            }
+            match chars.next() {
+                // SGR: \x1b[1;4m
+                Some('[') => {
+                    let mut parameters = String::new();
+                    for c in chars.by_ref() {
+                        if c.is_ascii_alphabetic() {
+                            break;
+                        }
+                        parameters.push(c);
+                    }
+                    if !current.is_empty() {
+                        spans.push(Span::styled(std::mem::take(&mut current), style));
+                    }
+                    style = sgr(style, &parameters);
+                },
+                // OSC, like the hyperlinks: there's nothing to click here, so skip to the terminator
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                            break;
+                        }
+                    }
+                },
+                _ => (),
+            }
+        }
+        if !current.is_empty() {
+            spans.push(Span::styled(current, style));
+        }
+        lines.push(Line::from(spans));
+    }
+    return Text::from(lines);
+}
+
+fn sgr(style: Style, parameters: &str) -> Style {
+    let codes = parameters.split(';').map(|code| code.parse::<u8>().unwrap_or_default()).collect::<Vec<u8>>();
+    let mut style = style;
+    let mut codes = codes.iter();
+    while let Some(code) = codes.next() {
+        style = match code {
+            0 => Style::default(),
+            1 => style.add_modifier(Modifier::BOLD),
+            2 => style.add_modifier(Modifier::DIM),
+            3 => style.add_modifier(Modifier::ITALIC),
+            4 => style.add_modifier(Modifier::UNDERLINED),
+            7 => style.add_modifier(Modifier::REVERSED),
+            22 => style.remove_modifier(Modifier::BOLD | Modifier::DIM),
+            23 => style.remove_modifier(Modifier::ITALIC),
+            24 => style.remove_modifier(Modifier::UNDERLINED),
+            30..=37 => style.fg(Color::Indexed(code - 30)),
+            90..=97 => style.fg(Color::Indexed(code - 90 + 8)),
+            39 => style.fg(Color::Reset),
+            40..=47 => style.bg(Color::Indexed(code - 40)),
+            49 => style.bg(Color::Reset),
+            // 38;5;n
+            38 | 48 if codes.next() == Some(&5) => match codes.next() {
+                Some(index) if *code == 38 => style.fg(Color::Indexed(*index)),
+                Some(index) => style.bg(Color::Indexed(*index)),
+                None => style,
+            },
+            _ => style,
+        };
+    }
+    return style;
+}
@@ -0,0 +1,90 @@
+// what the tests share: a miniature dump to look things up in, and a state to render entries with.
+// not every test uses all of it
+#![allow(dead_code)]
+
+// a multistream dump in miniature, laid out the way the real one is:
+// a stream with the <mediawiki> header, a stream per block of pages, and a last stream that closes it all off.
+// the index has a line per page, `offset:id:title`, with the offset of the stream the page is in
+
+use bzip2::write::BzEncoder;
+use bzip2::Compression;
+use dictionarium::markup::Format;
+use dictionarium::state::State;
+use dictionarium::theme::Color;
+use quick_xml::escape::escape;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// A page of a fixture dump: its title, id and wikitext, all unescaped
+pub type Fixture<'a> = (&'a str, u64, &'a str);
+
+/// Where a fixture dump was written
+pub struct Dump {
+    pub index: PathBuf,
+    pub dictionary: PathBuf,
+    /// The offset of each block's stream, in order
+    pub offsets: Vec<u64>,
+}
+
+pub fn compress(text: &str) -> Vec<u8> {
+    let mut encoder = BzEncoder::new(Vec::new(), Compression::fast());
+    encoder.write_all(text.as_bytes()).unwrap();
+    return encoder.finish().unwrap();
+}
+
+fn page_xml((title, id, text): &Fixture) -> String {
+    return format!("  <page>
+    <title>{}</title>
+    <ns>0</ns>
+    <id>{}</id>
+    <revision>
+      <id>{}</id>
+      <timestamp>2022-12-20T00:00:00Z</timestamp>
+      <model>wikitext</model>
+      <format>text/x-wiki</format>
+      <text bytes=\"{}\" xml:space=\"preserve\">{}</text>
+    </revision>
+  </page>
+", escape(*title), id, 70000000 + id, text.len(), escape(*text));
+}
+
+/// Writes a dump of `blocks`, each block its own bz2 stream, into a directory of its own called `name`
+pub fn dump(name: &str, blocks: &[&[Fixture]]) -> Dump {
+    let directory = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    fs::create_dir_all(&directory).unwrap();
+
+    let mut dictionary = compress("<mediawiki xmlns=\"http://www.mediawiki.org/xml/export-0.10/\" xml:lang=\"en\">\n  <siteinfo>\n    <sitename>Wiktionary</sitename>\n  </siteinfo>\n");
+    let mut index = String::new();
+    let mut offsets = Vec::new();
+    for block in blocks {
+        let offset = dictionary.len() as u64;
+        offsets.push(offset);
+        for page in block.iter() {
+            index.push_str(&format!("{}:{}:{}\n", offset, page.1, escape(page.0)));
+        }
+        dictionary.extend(compress(&block.iter().map(page_xml).collect::<String>()));
+    }
+    dictionary.extend(compress("</mediawiki>\n"));
+
+    let dump = Dump {
+        index: directory.join("index.txt.bz2"),
+        dictionary: directory.join("dictionary.xml.bz2"),
+        offsets,
+    };
+    fs::write(&dump.index, compress(&index)).unwrap();
+    fs::write(&dump.dictionary, dictionary).unwrap();
+    return dump;
+}
+
+/// A state to render with that comes out the same on any terminal: no colour, and `width` columns.
+/// Everything else that depends on where we're running is only filled in by `State::resolve`, which this skips
+pub fn state(format: Format, width: usize) -> State {
+    let mut state = State::new();
+    state.color = Color::Never;
+    state.width = Some(width);
+    state.format = format;
+    // everything, inflection tables and the other sections hidden by default included
+    state.exclude.clear();
+    return state;
+}
@@ -0,0 +1,85 @@
+#![allow(non_upper_case_globals)]
+
+// every page in tests/fixtures is rendered in each format and checked against the output saved beside it:
+// bank.wiki against bank.txt, bank.md and bank.html, with the file name as the title.
+// when the output is meant to change, `UPDATE_SNAPSHOTS=1 cargo test` saves the new output, and git diff shows what changed
+
+mod common;
+
+use dictionarium::display;
+use dictionarium::markup::Format;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const formats: &[(Format, &str)] = &[(Format::Terminal, "txt"), (Format::Markdown, "md"), (Format::Html, "html")];
+
+fn pages() -> Vec<PathBuf> {
+    let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures");
+    let mut pages = fs::read_dir(directory).unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "wiki"))
+        .collect::<Vec<_>>();
+    pages.sort();
+    return pages;
+}
+
+#[test]
+fn snapshots() {
+    let update = std::env::var_os("UPDATE_SNAPSHOTS").is_some_and(|value| !value.is_empty());
+    let pages = pages();
+    assert!(!pages.is_empty(), "there are no fixtures");
+
+    let mut failures = Vec::new();
+    for page in pages {
+        let title = page.file_stem().unwrap().to_str().unwrap();
+        let text = fs::read_to_string(&page).unwrap();
+        for (format, extension) in formats {
+            let rendered = display::display_to_string(title, &text, &common::state(*format, 80));
+            let snapshot = page.with_extension(extension);
+            if update {
+                fs::write(&snapshot, &rendered).unwrap();
+                continue;
+            }
+            match fs::read_to_string(&snapshot) {
+                Ok(expected) if expected == rendered => {},
+                Ok(expected) => failures.push(format!("{} differs:\n{}", snapshot.display(), diff(&expected, &rendered))),
+                Err(error) => failures.push(format!("Could not read {}: {}", snapshot.display(), error)),
+            }
+        }
+    }
+    assert!(failures.is_empty(), "{}\n\nIf that's intended, run the tests again with UPDATE_SNAPSHOTS=1.", failures.join("\n\n"));
+}
+
+/// The lines that changed, marked the way diff does: `-` for the snapshot's, `+` for the new output's
+fn diff(expected: &str, actual: &str) -> String {
+    let expected = expected.lines().collect::<Vec<_>>();
+    let actual = actual.lines().collect::<Vec<_>>();
+    // longest common subsequence, from the end. fixtures are small, so the whole table is fine
+    let mut common = vec![vec![0usize; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            common[i][j] = if expected[i] == actual[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            lines.push(format!("  {}", expected[i]));
+            i += 1;
+            j += 1;
+        } else if j == actual.len() || (i < expected.len() && common[i + 1][j] >= common[i][j + 1]) {
+            lines.push(format!("- {}", expected[i]));
+            i += 1;
+        } else {
+            lines.push(format!("+ {}", actual[j]));
+            j += 1;
+        }
+    }
+    return lines.join("\n");
+}
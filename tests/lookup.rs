@@ -0,0 +1,90 @@
+#![allow(non_upper_case_globals)]
+
+// looking pages up in a dump end to end: through the index, into the right stream, and out of the xml
+
+mod common;
+
+use common::{Dump, Fixture};
+use dictionarium::lookup;
+use dictionarium::page::Page;
+use dictionarium::state::State;
+use std::fs::File;
+
+const blocks: &[&[Fixture]] = &[
+    &[
+        ("dictionarium", 1, "==Latin==\n===Noun===\n# [[dictionary]]\n"),
+        ("AT&T", 2, "==English==\n===Proper noun===\n# A company & its <b>stuff</b>\n"),
+        ("go", 3, "==English==\n===Verb===\n# To move.\n"),
+    ],
+    &[
+        ("\"quoted\"", 4, "==English==\n===Noun===\n# Something in quotes.\n"),
+        ("dīcō", 5, "==Latin==\n===Verb===\n# to [[say]]\n"),
+        ("it's", 6, "==English==\n===Contraction===\n# it is\n"),
+    ],
+];
+
+fn lookup(dump: &Dump, word: &str) -> Option<Page> {
+    return lookup::lookup_local_in(word, File::open(&dump.index).unwrap(), &dump.dictionary).unwrap();
+}
+
+#[test]
+fn first_and_last_of_a_block() {
+    let dump = common::dump("first_and_last_of_a_block", blocks);
+    for (title, id, text) in [blocks[0][0], blocks[0][2], blocks[1][0], blocks[1][2]] {
+        let page = lookup(&dump, title).unwrap();
+        assert_eq!(page.title, title);
+        assert_eq!(page.id, id);
+        assert_eq!(page.text, text);
+    }
+}
+
+#[test]
+fn escaped_titles() {
+    let dump = common::dump("escaped_titles", blocks);
+    for (title, id, text) in [blocks[0][1], blocks[1][0], blocks[1][2]] {
+        let page = lookup(&dump, title).unwrap();
+        assert_eq!(page.title, title);
+        assert_eq!(page.id, id);
+        // the text is escaped in the dump too
+        assert_eq!(page.text, text);
+    }
+    // the escaped form is only how the index spells it, not a title of its own
+    assert!(lookup(&dump, "AT&amp;T").is_none());
+}
+
+#[test]
+fn missing_titles() {
+    let dump = common::dump("missing_titles", blocks);
+    assert!(lookup(&dump, "went").is_none());
+    assert!(lookup(&dump, "").is_none());
+    // titles are case sensitive, as on Wiktionary
+    assert!(lookup(&dump, "Go").is_none());
+    assert!(lookup(&dump, "dico").is_none());
+}
+
+#[test]
+fn whole_blocks() {
+    let dump = common::dump("whole_blocks", blocks);
+    for (block, offset) in blocks.iter().zip(dump.offsets.iter()) {
+        let pages = lookup::read_block_from(&dump.dictionary, *offset).unwrap();
+        let titles = pages.iter().map(|page| (page.title.as_str(), page.id)).collect::<Vec<_>>();
+        assert_eq!(titles, block.iter().map(|(title, id, _)| (*title, *id)).collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn malformed_index() {
+    let dump = common::dump("malformed_index", blocks);
+    std::fs::write(&dump.index, common::compress("not an index line\n")).unwrap();
+    let error = lookup::lookup_local_in("go", File::open(&dump.index).unwrap(), &dump.dictionary).unwrap_err();
+    assert!(error.to_string().contains("not an index line"));
+}
+
+#[test]
+fn missing_words_are_not_an_error() {
+    let dump = common::dump("missing_words_are_not_an_error", blocks);
+    let mut out = Vec::new();
+    let lookup = |word: &str| lookup::lookup_local_in(word, File::open(&dump.index)?, &dump.dictionary);
+    dictionarium::handle_word_with(String::from("nonexistent"), &State::new(), lookup, &mut out).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), "Could not find word nonexistent. Check your spelling?\n");
+}
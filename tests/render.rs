@@ -0,0 +1,289 @@
+#![allow(non_upper_case_globals)]
+
+// golden output for a few small entries, in each format.
+// colour is off and the width is fixed, so these come out the same whatever terminal runs them
+
+mod common;
+
+use dictionarium::display;
+use dictionarium::markup::{self, Format};
+use dictionarium::state::State;
+use dictionarium::theme::Color;
+
+const bank: &str = "\
+==English==
+
+===Etymology 1===
+From {{inh|en|enm|banke}}, from {{der|en|non|bakki|t=hill, bank}}.
+
+====Noun====
+{{en-noun}}
+
+# An [[edge]] of a river.
+#: {{ux|en|We sat on the '''bank'''.}}
+# A slope.
+## A small slope.
+# {{lb|en|aviation}} A tilt.
+
+===Etymology 2===
+From {{bor|en|it|banca|t=bench}}.
+
+====Noun====
+{{en-noun}}
+
+# An [[institution]] handling money.
+
+==French==
+
+===Noun===
+{{fr-noun|m}}
+
+# [[bench]]
+";
+
+const run: &str = "\
+==English==
+===Verb===
+# To move quickly.
+#* '''1611''', Someone, ''A Book'':
+#*: He did '''run''' fast.
+## To sprint.
+### To dash.
+### To bolt.
+#### Deeper.
+##: He sprinted.
+## To jog.
+# To operate.
+";
+
+const table: &str = "\
+==English==
+===Noun===
+{{en-noun}}
+
+# A piece of [[furniture]] with a [[flat]] top. See [[#Verb|below]].
+#: {{ux|en|Put it on the '''table'''.}}
+
+====Declension====
+{|
+|+ Forms
+! case !! form
+|-
+| nominative || table
+|-
+| genitive || table's & <co>
+|}
+
+===Verb===
+# To [[postpone]] *a* motion.
+";
+
+fn render(title: &str, definition: &str, format: Format) -> String {
+    return display::display_to_string(title, definition, &common::state(format, 60));
+}
+
+#[test]
+fn bank_terminal() {
+    assert_eq!(render("bank", bank, Format::Terminal), "\
+English
+bank¹ — From Middle English banke, from Old Norse bakki
+(“hill, bank”).
+Noun
+1. An edge of a river.
+     We sat on the bank.
+2. A slope.
+   2.1 A small slope.
+3. (aviation) A tilt.
+bank² — From Italian banca (“bench”).
+Noun
+1. An institution handling money.
+");
+}
+
+#[test]
+fn nested_senses_terminal() {
+    assert_eq!(render("run", run, Format::Terminal), "\
+English
+Verb
+1. To move quickly.
+   • 1611, Someone, A Book:
+       He did run fast.
+   1.1 To sprint.
+       1.1.a To dash.
+       1.1.b To bolt.
+             1.1.b.a Deeper.
+         He sprinted.
+   1.2 To jog.
+2. To operate.
+");
+}
+
+#[test]
+fn table_terminal() {
+    assert_eq!(render("table", table, Format::Terminal), "\
+English
+Noun
+1. A piece of furniture with a flat top. See below.
+     Put it on the table.
+Declension
+Forms
+case       │ form
+nominative │ table
+genitive   │ table's & <co>
+Verb
+1. To postpone *a* motion.
+");
+}
+
+#[test]
+fn bank_markdown() {
+    assert_eq!(render("bank", bank, Format::Markdown), "\
+# English
+
+**bank¹** — From Middle English banke, from Old Norse bakki (“hill, bank”).
+
+### Noun
+
+1. An [edge](https://en.wiktionary.org/wiki/edge) of a river.
+   - We sat on the **bank**.
+2. A slope.
+   1. A small slope.
+3. (aviation) A tilt.
+
+**bank²** — From Italian banca (“bench”).
+
+### Noun
+
+1. An [institution](https://en.wiktionary.org/wiki/institution) handling money.
+
+");
+}
+
+#[test]
+fn table_markdown() {
+    assert_eq!(render("table", table, Format::Markdown), "\
+# English
+
+## Noun
+
+1. A piece of [furniture](https://en.wiktionary.org/wiki/furniture) with a [flat](https://en.wiktionary.org/wiki/flat) top. See [below](https://en.wiktionary.org/wiki/table#Verb).
+   - Put it on the **table**.
+
+### Declension
+
+Forms
+
+| case | form |
+| --- | --- |
+| nominative | table |
+| genitive | table's & \\<co\\> |
+
+## Verb
+
+1. To [postpone](https://en.wiktionary.org/wiki/postpone) \\*a\\* motion.
+
+");
+}
+
+#[test]
+fn nested_senses_html() {
+    assert_eq!(render("run", run, Format::Html), "\
+<article id=\"run\">
+<h1 id=\"English\">English</h1>
+<h2 id=\"Verb\">Verb</h2>
+<ol>
+<li>To move quickly.
+<ul>
+<li><b>1611</b>, Someone, <i>A Book</i>:
+<dl>
+<dd class=\"example\">He did <b>run</b> fast.</dd>
+</dl>
+</li>
+</ul>
+<ol>
+<li>To sprint.
+<ol>
+<li>To dash.</li>
+<li>To bolt.
+<ol>
+<li>Deeper.</li>
+</ol>
+</li>
+</ol>
+<dl>
+<dd class=\"example\">He sprinted.</dd>
+</dl>
+</li>
+<li>To jog.</li>
+</ol>
+</li>
+<li>To operate.</li>
+</ol>
+</article>
+");
+}
+
+#[test]
+fn table_html() {
+    assert_eq!(render("table", table, Format::Html), "\
+<article id=\"table\">
+<h1 id=\"English\">English</h1>
+<h2 id=\"Noun\">Noun</h2>
+<ol>
+<li>A piece of <a href=\"https://en.wiktionary.org/wiki/furniture\">furniture</a> with a <a href=\"https://en.wiktionary.org/wiki/flat\">flat</a> top. See <a href=\"#Verb\">below</a>.
+<dl>
+<dd class=\"example\">Put it on the <b>table</b>.</dd>
+</dl>
+</li>
+</ol>
+<h3 id=\"Declension\">Declension</h3>
+<table>
+<caption>Forms</caption>
+<tr><th>case</th><th>form</th></tr>
+<tr><td>nominative</td><td>table</td></tr>
+<tr><td>genitive</td><td>table&#39;s &amp; &lt;co&gt;</td></tr>
+</table>
+<h2 id=\"Verb\">Verb</h2>
+<ol>
+<li>To <a href=\"https://en.wiktionary.org/wiki/postpone\">postpone</a> *a* motion.</li>
+</ol>
+</article>
+");
+}
+
+#[test]
+fn styled_with_links() {
+    let mut state = common::state(Format::Terminal, 60);
+    state.color = Color::Always;
+    state.links = true;
+    state.exclude = State::new().exclude;
+    assert_eq!(display::display_to_string("table", table, &state), "\
+\x1b[1mEnglish\x1b[0m
+\x1b[1mNoun\x1b[0m
+\x1b[1m1.\x1b[0m A piece of \x1b[4mfurniture\x1b[0m[1] with a \x1b[4mflat\x1b[0m[2] top. See \x1b[4mbelow\x1b[0m[3].
+     \x1b[2mPut it on the \x1b[0m\x1b[2m\x1b[1mtable\x1b[0m\x1b[2m.\x1b[0m
+\x1b[1mVerb\x1b[0m
+\x1b[1m1.\x1b[0m To \x1b[4mpostpone\x1b[0m[4] *a* motion.
+[1] https://en.wiktionary.org/wiki/furniture
+[2] https://en.wiktionary.org/wiki/flat
+[3] https://en.wiktionary.org/wiki/table#Verb
+[4] https://en.wiktionary.org/wiki/postpone
+");
+}
+
+#[test]
+fn display_writes_the_same() {
+    let mut out = Vec::new();
+    display::display("run", run, &common::state(Format::Terminal, 60), &mut out).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), render("run", run, Format::Terminal));
+}
+
+#[test]
+fn one_html_page_for_several_entries() {
+    let body = render("run", run, Format::Html) + &render("table", table, Format::Html);
+    let page = markup::document("run, table", &body);
+    assert!(page.starts_with("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>run, table</title>\n</head>\n<body>\n<article id=\"run\">\n"));
+    assert!(page.ends_with("</article>\n</body>\n</html>\n"));
+    assert_eq!(page.matches("<!DOCTYPE").count(), 1);
+    assert_eq!(page.matches("<article").count(), 2);
+}
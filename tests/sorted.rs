@@ -0,0 +1,51 @@
+#![allow(non_upper_case_globals)]
+
+// sorted index files: written in order however the lines come in, and searched by prefix
+
+use dictionarium::sorted;
+use std::path::PathBuf;
+
+const words: &[&str] = &["bank", "bakki", "AT&T", "dīcō", "banca", "went", "bank account", "go", "dictionarium", "bank", "zymurgy"];
+
+fn write(name: &str, run_size: usize) -> PathBuf {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(format!("{}.txt", name));
+    let mut writer = sorted::Writer::with_run_size(&path, run_size);
+    for word in words {
+        writer.push(format!("{}\t{}", word, word.len())).unwrap();
+    }
+    writer.finish().unwrap();
+    return path;
+}
+
+fn starting_with(path: &PathBuf, prefix: &str) -> Vec<String> {
+    return sorted::starting_with(path, prefix).unwrap().map(|line| line.unwrap()).collect();
+}
+
+#[test]
+fn sorted_without_duplicates() {
+    // spilling every line or two, or never
+    for (name, run_size) in [("one_run", usize::MAX), ("many_runs", 10), ("a_run_a_line", 0)] {
+        let path = write(name, run_size);
+        let lines = std::fs::read_to_string(&path).unwrap();
+        let mut expected = words.iter().map(|word| format!("{}\t{}\n", word, word.len())).collect::<Vec<_>>();
+        expected.sort();
+        expected.dedup();
+        assert_eq!(lines, expected.concat(), "{}", name);
+        // and the runs are cleaned up after
+        assert!(!PathBuf::from(format!("{}.run0", path.display())).exists(), "{}", name);
+    }
+}
+
+#[test]
+fn prefixes() {
+    let path = write("prefixes", usize::MAX);
+    assert_eq!(starting_with(&path, "ban"), ["banca\t5", "bank\t4", "bank account\t12"]);
+    assert_eq!(starting_with(&path, "bank\t"), ["bank\t4"]);
+    // the first line and the last
+    assert_eq!(starting_with(&path, "AT"), ["AT&T\t4"]);
+    assert_eq!(starting_with(&path, "zy"), ["zymurgy\t7"]);
+    assert_eq!(starting_with(&path, "dī"), ["dīcō\t6"]);
+    assert!(starting_with(&path, "bz").is_empty());
+    assert!(starting_with(&path, "~").is_empty());
+    assert_eq!(starting_with(&path, "").len(), words.len() - 1);
+}
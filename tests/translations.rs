@@ -0,0 +1,34 @@
+#![allow(non_upper_case_globals)]
+
+// translation tables, as parsed out of a page
+
+use dictionarium::translations;
+
+const bank: &str = "\
+==English==
+
+===Noun===
+# An institution handling money.
+
+====Translations====
+{{trans-top|financial institution}}
+* French: {{t+|fr|banque|f}}
+* Russian: {{t+|ru|банк|m|alt=ба́нк|tr=bank}}
+{{trans-bottom}}
+";
+
+#[test]
+fn alternative_forms() {
+    let definition = parse_wiki_text::Configuration::default().parse(bank);
+    let tables = translations::parse(&definition, "English");
+    assert_eq!(tables.len(), 1);
+    assert_eq!(tables[0].gloss, "financial institution");
+    let [french, russian] = tables[0].translations.as_slice() else {
+        panic!("expected two translations, got {:?}", tables[0].translations);
+    };
+    assert_eq!((french.term.as_str(), french.alt.as_deref()), ("banque", None));
+    assert_eq!(french.to_string(), "banque (f)");
+    // looked up by the page, shown as written
+    assert_eq!((russian.term.as_str(), russian.alt.as_deref()), ("банк", Some("ба́нк")));
+    assert_eq!(russian.to_string(), "ба́нк (m) [bank]");
+}